@@ -1,8 +1,14 @@
-use clap::Parser;
-use rsrpp::parser::parse;
+use clap::{Parser, ValueEnum};
 use rsrpp::parser::structs::{ParserConfig, Section};
+use rsrpp::parser::{parse, sections_to_markdown};
 use std::path::Path;
 
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+enum Format {
+    Json,
+    Markdown,
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about=None)]
 struct Args {
@@ -14,6 +20,9 @@ struct Args {
 
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
+
+    #[arg(short, long, value_enum, default_value_t = Format::Json)]
+    format: Format,
 }
 
 #[tokio::main]
@@ -26,16 +35,24 @@ async fn main() {
         std::process::exit(-1);
     }
 
-    let outfile = args.out.unwrap_or("output.json".to_string());
+    let extension = match args.format {
+        Format::Json => ".json",
+        Format::Markdown => ".md",
+    };
+    let outfile = args.out.unwrap_or(format!("output{}", extension));
     assert!(
-        outfile.ends_with(".json"),
-        "Output file must be a JSON file"
+        outfile.ends_with(extension),
+        "Output file must be a {} file",
+        extension
     );
 
     let mut config = ParserConfig::new();
     let pages = parse(args.pdf.as_str(), &mut config, args.verbose).await.unwrap();
-    let sections = Section::from_pages(&pages);
-    let json = serde_json::to_string_pretty(&sections).unwrap();
+    let sections = Section::from_pages_with_config(&pages, &config);
+    let output = match args.format {
+        Format::Json => serde_json::to_string_pretty(&sections).unwrap(),
+        Format::Markdown => sections_to_markdown(&sections),
+    };
 
-    std::fs::write(format!("{}", outfile), json).unwrap();
+    std::fs::write(format!("{}", outfile), output).unwrap();
 }
@@ -0,0 +1,114 @@
+use std::fmt;
+
+/// The `ParseError` enum represents the causes for which parsing a PDF document can fail.
+///
+/// # Variants
+///
+/// * `MissingCommand` - Required poppler commands are not installed, e.g. "pdftocairo, pdftotext".
+/// * `Download` - The PDF document could not be downloaded.
+/// * `InvalidPdf` - `pdfinfo` could not read the PDF document, e.g. because the file is broken or the URL is not a PDF.
+/// * `EmptyDocument` - The PDF document contains no text.
+/// * `XmlTimeout` - `pdftohtml` or `pdftotext` did not write its output in time.
+/// * `Opencv` - An OpenCV operation on the page images failed.
+/// * `Io` - A file or process operation failed.
+/// * `InsideRuntime` - `parse_blocking` was called from within an async runtime.
+/// * `InvalidConfig` - A `ParserConfigBuilder` setting cannot be used, e.g. a DPI of 0.
+/// * `Other` - Any other error.
+#[derive(Debug)]
+pub enum ParseError {
+    MissingCommand(String),
+    Download(reqwest::Error),
+    InvalidPdf,
+    EmptyDocument,
+    XmlTimeout,
+    Opencv(String),
+    Io(std::io::Error),
+    InsideRuntime,
+    InvalidConfig(String),
+    Other(anyhow::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingCommand(command) => {
+                write!(
+                    f,
+                    "Error: command not found: {}; install poppler-utils",
+                    command
+                )
+            }
+            ParseError::Download(e) => write!(f, "Error: failed to download the pdf file: {}", e),
+            ParseError::InvalidPdf => write!(f, "Error: pdf file is broken or invalid url"),
+            ParseError::EmptyDocument => write!(f, "Error: pdf file contains no text"),
+            ParseError::XmlTimeout => write!(f, "Error: timed out waiting for the converted file"),
+            ParseError::Opencv(message) => write!(f, "Error: opencv: {}", message),
+            ParseError::Io(e) => write!(f, "Error: {}", e),
+            ParseError::InsideRuntime => write!(
+                f,
+                "Error: parse_blocking cannot be called from within an async runtime, use parse instead"
+            ),
+            ParseError::InvalidConfig(message) => {
+                write!(f, "Error: invalid configuration: {}", message)
+            }
+            ParseError::Other(e) => write!(f, "Error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Download(e) => Some(e),
+            ParseError::Io(e) => Some(e),
+            ParseError::Other(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ParseError {
+    fn from(e: reqwest::Error) -> ParseError {
+        return ParseError::Download(e);
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> ParseError {
+        return ParseError::Io(e);
+    }
+}
+
+impl From<opencv::Error> for ParseError {
+    fn from(e: opencv::Error) -> ParseError {
+        return ParseError::Opencv(e.to_string());
+    }
+}
+
+impl From<anyhow::Error> for ParseError {
+    /// Recovers a `ParseError` raised inside a function returning `anyhow::Result`,
+    /// and wraps any other error in `ParseError::Other`.
+    fn from(e: anyhow::Error) -> ParseError {
+        return match e.downcast::<ParseError>() {
+            Ok(e) => e,
+            Err(e) => ParseError::Other(e),
+        };
+    }
+}
+
+/// Converts the error of spawning a command into a `ParseError`.
+///
+/// # Arguments
+///
+/// * `command` - The name of the command, e.g. "pdfinfo".
+/// * `e` - The error returned when spawning the command.
+///
+/// # Returns
+///
+/// `ParseError::MissingCommand` if the command was not found, or `ParseError::Io` otherwise.
+pub fn command_error(command: &str, e: std::io::Error) -> ParseError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        return ParseError::MissingCommand(command.to_string());
+    }
+    return ParseError::Io(e);
+}
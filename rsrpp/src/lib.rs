@@ -28,11 +28,16 @@
 //!
 //! ```rust
 //! # use rsrpp::parser::structs::{ParserConfig, Section};
-//! # use rsrpp::parser::{parse, pages2json};
+//! # use rsrpp::parser::{parse, parse_paper, pages2json};
 //! # async fn try_main() -> Result<(), String> {
 //! let mut config = ParserConfig::new();
 //! let verbose = true;
 //! let url = "https://arxiv.org/pdf/1706.03762";
+//! let paper = parse_paper(url, &mut config, verbose).await.unwrap(); // Paper
+//! let abstract_text = paper.abstract_text(); // Option<String>
+//! let references = paper.references(); // &Vec<Reference>
+//!
+//! // the lower-level functions remain available
 //! let pages = parse(url, &mut config, verbose).await.unwrap(); // Vec<Page>
 //! let sections = Section::from_pages(&pages); // Vec<Section>
 //! let json = serde_json::to_string(&sections).unwrap(); // String
@@ -54,4 +59,5 @@
 #[cfg(test)]
 mod tests;
 
+pub mod error;
 pub mod parser;
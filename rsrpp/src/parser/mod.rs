@@ -1,5 +1,7 @@
+use crate::error::{command_error, ParseError};
 use crate::parser::structs::*;
 use anyhow::{Error, Result};
+use base64::Engine;
 use glob::glob;
 use indicatif::ProgressBar;
 use opencv::core::{Vec4f, Vector};
@@ -7,20 +9,61 @@ use opencv::imgcodecs;
 use opencv::imgproc;
 use opencv::prelude::*;
 use quick_xml::events::Event;
+use rayon::prelude::*;
 use reqwest as request;
 use scraper::html;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::f64::consts::PI;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use std::process::Command;
 use std::process::Stdio;
+use std::sync::LazyLock;
 use std::time::Duration;
 
 #[cfg(test)]
 mod tests;
 
+/// The IoU above which two detected regions are treated as the same region.
+const REGION_IOU_THRESHOLD: f32 = 0.5;
+
+/// The IoU above which regions on different pages are treated as the same repeated region.
+const REPEATED_REGION_IOU_THRESHOLD: f32 = 0.9;
+
+/// The minimum number of pages a region must appear on to be treated as a logo or watermark.
+const MIN_REPEATED_REGION_PAGES: usize = 3;
+
+/// The minimum number of pages a block must appear on to be treated as a running header or footer.
+const MIN_RUNNING_HEADER_PAGES: usize = 3;
+
+/// The height, relative to the page height, of the top and bottom margins in which running headers and footers are detected.
+const RUNNING_HEADER_MARGIN_RATIO: f32 = 0.08;
+
+/// The maximum difference in y-position, relative to the page height, of the same running header on different pages.
+const RUNNING_HEADER_Y_TOLERANCE: f32 = 0.01;
+
+/// The minimum number of numbered text elements in a style for it to be taken as the subsection heading style.
+const MIN_SUBSECTION_HEADINGS: usize = 2;
+
+/// The minimum number of increasing numbers in a page margin that are treated as line numbers.
+const MIN_MARGIN_LINE_NUMBERS: usize = 5;
+
+/// The top of the area, relative to the page height, in which footnotes are detected.
+const FOOTNOTE_TOP_RATIO: f32 = 0.7;
+
+/// The font size, relative to the body text, below which a line at the bottom of a page is a footnote.
+const FOOTNOTE_FONT_RATIO: f32 = 0.9;
+
+/// The minimum number of cells of a grid of ruling lines that is detected as a table.
+const MIN_GRID_CELLS: usize = 2;
+
+/// The minimum width and height in pixels of a page image on which tables are detected.
+const MIN_TABLE_IMAGE_SIZE: i32 = 16;
+
+/// The minimum width and height of a word, used when `pdftotext` reports an empty box.
+const MIN_WORD_SIZE: f32 = 0.1;
+
 pub mod structs;
 
 /// Retrieves information about a PDF document using the `pdfinfo` command.
@@ -31,15 +74,20 @@ pub mod structs;
 ///
 /// # Returns
 ///
-/// A `Result` which is `Ok` if the information was successfully retrieved, or an `Err` if an error occurred.
-fn get_pdf_info(config: &mut ParserConfig, verbose: bool, time: std::time::Instant) -> Result<()> {
+/// A `Result` which is `Ok` if the information was successfully retrieved, or a `ParseError` if an error occurred.
+fn get_pdf_info(
+    config: &mut ParserConfig,
+    verbose: bool,
+    time: std::time::Instant,
+) -> Result<(), ParseError> {
     let res =
         Command::new("pdfinfo").args(&[config.pdf_path.clone()]).stdout(Stdio::piped()).output();
-    let text = String::from_utf8(res?.stdout)?;
+    let res = res.map_err(|e| command_error("pdfinfo", e))?;
+    let text = String::from_utf8_lossy(&res.stdout).to_string();
 
     //Syntax Error: Document stream is empty
     if text.is_empty() {
-        return Err(Error::msg("Error: pdf file is broken or invalid url"));
+        return Err(ParseError::InvalidPdf);
     }
 
     for line in text.split("\n") {
@@ -51,7 +99,7 @@ fn get_pdf_info(config: &mut ParserConfig, verbose: bool, time: std::time::Insta
         let value = parts[1].trim().to_string();
 
         if key == "page_size" {
-            let regex = regex::Regex::new(r"([\d|\.]+) x ([\d|\.]+).*?")?;
+            let regex = regex::Regex::new(r"([\d|\.]+) x ([\d|\.]+).*?").unwrap();
             let caps = regex.captures(&value).unwrap();
             config.pdf_info.insert("page_width".to_string(), caps[1].to_string());
             config.pdf_info.insert("page_height".to_string(), caps[2].to_string());
@@ -87,14 +135,14 @@ fn save_pdf_as_figures(
         .args(&[
             "-jpeg".to_string(),
             "-r".to_string(),
-            "72".to_string(),
+            config.dpi.to_string(),
             pdf_path.to_str().unwrap().to_string(),
             dst_path.to_str().unwrap().to_string(),
         ])
         .stdout(Stdio::piped())
         .output();
     if let Err(e) = res {
-        return Err(Error::msg(format!("Error: {}", e)));
+        return Err(command_error("pdftocairo", e).into());
     }
 
     // get all jpeg files
@@ -120,15 +168,7 @@ fn save_pdf_as_figures(
     for entry in glob(glob_query.to_str().unwrap())? {
         match entry {
             Ok(path) => {
-                let page_number: PageNumber = path
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .split("-")
-                    .last()
-                    .unwrap()
-                    .parse::<i8>()?;
+                let page_number = figure_page_number(&path)?;
                 config.pdf_figures.insert(page_number, path.to_str().unwrap().to_string());
             }
             Err(e) => return Err(Error::msg(format!("Error: {}", e))),
@@ -145,6 +185,79 @@ fn save_pdf_as_figures(
     return Ok(());
 }
 
+/// Returns the page number of a figure rendered by `pdftocairo`, e.g. 12 for "pdf_x-012.jpg".
+///
+/// # Arguments
+///
+/// * `path` - The path of the figure.
+///
+/// # Returns
+///
+/// A `Result` containing the page number, or an `Err` if the file name has no page suffix.
+fn figure_page_number(path: &Path) -> Result<PageNumber> {
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    let suffix = stem.split("-").last().unwrap_or_default();
+    return Ok(suffix.parse::<PageNumber>()?);
+}
+
+/// Reconciles the pages rendered by `pdftocairo` with the pages in the `pdftohtml` XML.
+///
+/// Pages that are present in the XML but missing from `config.pdf_figures` are mapped to a
+/// blank white image so that table detection can proceed on every page.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the figure and XML paths.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if every XML page has a figure, or an `Err` if a blank figure could not be written.
+fn reconcile_figures(config: &mut ParserConfig, verbose: bool) -> Result<()> {
+    let xml_text = std::fs::read_to_string(&config.pdf_xml_path)?;
+    let page_regex = regex::Regex::new(r#"<page number="(\d+)""#).unwrap();
+    let xml_pages = page_regex
+        .captures_iter(&xml_text)
+        .map(|caps| caps[1].parse::<PageNumber>())
+        .collect::<Result<Vec<PageNumber>, _>>()?;
+
+    for page_number in config.pdf_figures.keys() {
+        if !xml_pages.contains(page_number) && verbose {
+            println!(
+                "Warning: page {} was rendered but is missing in XML",
+                page_number
+            );
+        }
+    }
+
+    let width = config.pdf_info.get("page_width").and_then(|w| w.parse::<f32>().ok());
+    let height = config.pdf_info.get("page_height").and_then(|h| h.parse::<f32>().ok());
+    let pdf_path = Path::new(config.pdf_path.as_str());
+    let dst_path = pdf_path.parent().unwrap().join(pdf_path.file_stem().unwrap().to_str().unwrap());
+    for page_number in xml_pages {
+        if config.pdf_figures.contains_key(&page_number) {
+            continue;
+        }
+        if verbose {
+            println!(
+                "Warning: page {} is missing in figures, using a blank image",
+                page_number
+            );
+        }
+        let blank = Mat::new_rows_cols_with_default(
+            (height.unwrap_or(792.0) * config.render_zoom()) as i32,
+            (width.unwrap_or(612.0) * config.render_zoom()) as i32,
+            opencv::core::CV_8UC3,
+            opencv::core::Scalar::all(255.0),
+        )
+        .map_err(ParseError::from)?;
+        let blank_path = format!("{}-{}.jpg", dst_path.to_str().unwrap(), page_number);
+        imgcodecs::imwrite_def(&blank_path, &blank).map_err(ParseError::from)?;
+        config.pdf_figures.insert(page_number, blank_path);
+    }
+
+    return Ok(());
+}
+
 /// Saves the content of a PDF document as an XML file using the `pdftohtml` command.
 ///
 /// # Arguments
@@ -173,7 +286,8 @@ fn save_pdf_as_xml(
             xml_path.to_str().unwrap().to_string(),
         ])
         .stdout(Stdio::piped())
-        .output()?;
+        .output()
+        .map_err(|e| command_error("pdftohtml", e))?;
 
     // assert that the xml file exists
     let mut retry_count = 300;
@@ -182,7 +296,7 @@ fn save_pdf_as_xml(
             break;
         }
         if retry_count == 0 {
-            return Err(Error::msg("Error: Failed to save PDF as XML file"));
+            return Err(ParseError::XmlTimeout.into());
         } else {
             std::thread::sleep(Duration::from_secs(1));
             retry_count -= 1;
@@ -193,52 +307,15 @@ fn save_pdf_as_xml(
         }
     }
 
-    // get title font size
-    let mut font_number = 0;
     let xml_text = std::fs::read_to_string(xml_path)?;
-    let mut reader = quick_xml::Reader::from_str(&xml_text);
-    reader.config_mut().trim_text(true);
-    loop {
-        match reader.read_event() {
-            Ok(Event::Start(e)) => {
-                if e.name().as_ref() == b"text" {
-                    for attr in e.attributes() {
-                        let attr = attr?;
-                        if attr.key.as_ref() == b"font" {
-                            font_number = String::from_utf8_lossy(attr.value.as_ref())
-                                .parse::<i32>()
-                                .unwrap();
-                        }
-                    }
-                }
-            }
-            Ok(Event::Text(e)) => {
-                if String::from_utf8_lossy(e.as_ref()).to_lowercase() == "abstract"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "introduction"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "related work"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "related works"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "experiments"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "conclusion"
-                    || String::from_utf8_lossy(e.as_ref()).to_lowercase() == "references"
-                {
-                    break;
-                }
-            }
-            Ok(Event::Eof) => {
-                break;
-            }
-            Err(_e) => {
-                break;
-            }
-            _ => {}
-        }
-    }
 
-    if verbose {
-        println!(
-            "Extracted Title Font Size in {:.2}s",
-            time.elapsed().as_secs()
-        );
+    // a user-provided detector replaces the font-based detection
+    if let Some(detector) = config.section_detector.clone() {
+        config.sections = detector.detect(&xml_text);
+        if verbose {
+            println!("Converted PDf into XML in {:.2}s", time.elapsed().as_secs());
+        }
+        return Ok(());
     }
 
     // get sections
@@ -256,75 +333,270 @@ fn save_pdf_as_xml(
     } else {
         None
     };
+    let sections = detect_section_titles(&xml_text, &config.section_keywords)?;
+    config.sections.extend(sections);
+
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    if verbose {
+        println!("Converted PDf into XML in {:.2}s", time.elapsed().as_secs());
+    }
+
+    return Ok(());
+}
+
+/// Reads the font specifications and text elements of the XML generated by `pdftohtml`.
+///
+/// # Arguments
+///
+/// * `xml_text` - The XML generated by `pdftohtml`.
+///
+/// # Returns
+///
+/// A `Result` containing the `FontSpec` instances by identifier and the `XmlText` instances in
+/// document order.
+fn read_xml_texts(xml_text: &str) -> Result<(HashMap<i32, FontSpec>, Vec<XmlText>)> {
+    let mut fonts: HashMap<i32, FontSpec> = HashMap::new();
+    let mut texts: Vec<XmlText> = Vec::new();
     let mut page_number = 0;
-    let mut is_title = false;
-    let regex_is_number = regex::Regex::new(r"^\d+$").unwrap();
-    let regex_trim_number = regex::Regex::new(r"\d\.").unwrap();
-    let mut reader = quick_xml::Reader::from_str(&xml_text);
-    reader.config_mut().trim_text(true);
+    let mut current: Option<XmlText> = None;
+    let mut bold_depth = 0;
+    let mut all_bold = true;
+    let mut reader = quick_xml::Reader::from_str(xml_text);
     loop {
         match reader.read_event() {
-            Ok(Event::Start(e)) => {
-                if e.name().as_ref() == b"page" {
-                    for attr in e.attributes() {
-                        let attr = attr?;
-                        if attr.key.as_ref() == b"number" {
-                            page_number =
-                                String::from_utf8_lossy(attr.value.as_ref()).parse::<i8>().unwrap();
-                        }
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let attrs = e
+                    .attributes()
+                    .flatten()
+                    .map(|attr| {
+                        (
+                            String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                            String::from_utf8_lossy(attr.value.as_ref()).to_string(),
+                        )
+                    })
+                    .collect::<HashMap<String, String>>();
+                let number = |key: &str| -> f32 {
+                    return attrs
+                        .get(key)
+                        .and_then(|value| value.parse::<f32>().ok())
+                        .unwrap_or(0.0);
+                };
+                match e.name().as_ref() {
+                    b"page" => {
+                        page_number = number("number") as PageNumber;
                     }
-                } else if e.name().as_ref() == b"text" {
-                    let _font_number = String::from_utf8_lossy(
-                        e.attributes()
-                            .find(|attr| attr.clone().unwrap().key.as_ref() == b"font")
-                            .unwrap()
-                            .unwrap()
-                            .value
-                            .as_ref(),
-                    )
-                    .parse::<i32>()
-                    .unwrap();
-
-                    if font_number == _font_number {
-                        is_title = true;
-                    } else {
-                        is_title = false;
+                    b"fontspec" => {
+                        let id = number("id") as i32;
+                        fonts.insert(
+                            id,
+                            FontSpec {
+                                id: id,
+                                size: number("size"),
+                                family: attrs.get("family").cloned().unwrap_or_default(),
+                            },
+                        );
                     }
-                    continue;
+                    b"text" => {
+                        current = Some(XmlText {
+                            page_number: page_number,
+                            top: number("top"),
+                            left: number("left"),
+                            width: number("width"),
+                            font: number("font") as i32,
+                            bold: false,
+                            text: String::new(),
+                        });
+                        bold_depth = 0;
+                        all_bold = true;
+                    }
+                    b"b" => {
+                        bold_depth += 1;
+                    }
+                    _ => {}
                 }
             }
             Ok(Event::Text(e)) => {
-                let text = String::from_utf8_lossy(e.as_ref());
-                if regex_is_number.is_match(&text) {
-                    continue;
-                }
-                let text = regex_trim_number.replace(&text, "").to_string().trim().to_string();
-                if is_title {
-                    config.sections.push((page_number, text.to_string()));
-                    if text.to_lowercase() == "references" {
-                        break;
+                if let Some(text) = current.as_mut() {
+                    let content = String::from_utf8_lossy(e.as_ref()).to_string();
+                    if bold_depth == 0 && !content.trim().is_empty() {
+                        all_bold = false;
                     }
+                    text.text.push_str(&content);
                 }
             }
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"b" => {
+                    bold_depth -= 1;
+                }
+                b"text" => {
+                    if let Some(mut text) = current.take() {
+                        text.text = text.text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                        let bold_font = fonts.get(&text.font).map_or(false, |font| font.is_bold());
+                        text.bold = bold_font || (all_bold && !text.text.is_empty());
+                        texts.push(text);
+                    }
+                }
+                _ => {}
+            },
             Ok(Event::Eof) => {
                 break;
             }
-            Err(_e) => {
-                break;
+            Err(e) => {
+                return Err(Error::msg(format!("Error: failed to read XML: {}", e)));
             }
             _ => {}
         }
     }
+    return Ok((fonts, texts));
+}
 
-    if let Some(pb) = pb {
-        pb.finish_and_clear();
+/// Detects the section titles in the XML generated by `pdftohtml`.
+///
+/// The style (font size and weight) shared by most of the text elements with a well-known title
+/// such as "Abstract" or "Introduction" (`keywords`) is taken as the heading style, and every text element
+/// in that style is a section title. Font statistics are collected over the whole document, so
+/// headings are found even when `pdftohtml` assigns them different font identifiers. When the
+/// headings have the same size as the body text and differ only by weight, bold text that
+/// continues or is continued by regular text on the same line is treated as inline emphasis.
+///
+/// Subsections are often set in a smaller style than the sections, so the style other than the
+/// body text shared by the most text elements numbered like "3.1" is taken as a second heading style,
+/// in which only the numbered text elements are section titles. Their depth is recovered from the
+/// number later, see `Section::level`.
+///
+/// # Arguments
+///
+/// * `xml_text` - The XML generated by `pdftohtml`.
+/// * `keywords` - The well-known section titles, see `ParserConfig::section_keywords`.
+///
+/// # Returns
+///
+/// A `Result` containing the page number and the title of each section in document order,
+/// up to and including "References".
+fn detect_section_titles(xml_text: &str, keywords: &[String]) -> Result<Vec<(PageNumber, String)>> {
+    let (fonts, texts) = read_xml_texts(xml_text)?;
+    let style = |text: &XmlText| -> (i32, bool) {
+        let size = fonts.get(&text.font).map_or(0.0, |font| font.size);
+        return ((size * 2.0).round() as i32, text.bold);
+    };
+
+    // the body text style is the style covering the most characters
+    let mut style_counts: HashMap<(i32, bool), usize> = HashMap::new();
+    for text in texts.iter() {
+        *style_counts.entry(style(text)).or_insert(0) += text.text.chars().count();
     }
+    let body_style = match style_counts.into_iter().max_by_key(|(_, count)| *count) {
+        Some((body_style, _)) => body_style,
+        None => return Ok(Vec::new()),
+    };
 
-    if verbose {
-        println!("Converted PDf into XML in {:.2}s", time.elapsed().as_secs());
+    let keyword_texts = texts
+        .iter()
+        .filter(|text| {
+            let title = strip_section_number(&text.text).to_lowercase();
+            return keywords.iter().any(|keyword| keyword.trim().to_lowercase() == title);
+        })
+        .collect::<Vec<&XmlText>>();
+
+    // a keyword can also appear outside the headings, e.g. an "Abstract" label in the author
+    // block, so the style shared by the most keywords wins, and the earliest one on a tie
+    let mut keyword_counts: Vec<((i32, bool), usize)> = Vec::new();
+    for text in keyword_texts.iter() {
+        match keyword_counts.iter_mut().find(|(key, _)| *key == style(text)) {
+            Some((_, count)) => *count += 1,
+            None => keyword_counts.push((style(text), 1)),
+        }
+    }
+    let heading_style = match keyword_counts.iter().rev().max_by_key(|(_, count)| *count) {
+        Some((heading_style, _)) => *heading_style,
+        None => return Ok(Vec::new()),
+    };
+    let heading = *keyword_texts.iter().find(|text| style(text) == heading_style).unwrap();
+
+    let regex_is_number = regex::Regex::new(r"^\d+(\.\d+)*\.?$").unwrap();
+    let regex_subsection = regex::Regex::new(r"^[1-9]\d*(\.\d+)+\.?\s+\p{Lu}").unwrap();
+    let regex_subsection_number = regex::Regex::new(r"^[1-9]\d*(\.\d+)+\.?$").unwrap();
+    let is_numbered_subsection = |i: usize| -> bool {
+        let text = &texts[i];
+        if regex_subsection.is_match(&text.text) {
+            return true;
+        }
+        // the number may be a text element of its own to the left of the title
+        if i == 0 || !text.text.starts_with(char::is_uppercase) {
+            return false;
+        }
+        let number = &texts[i - 1];
+        return number.page_number == text.page_number
+            && (number.top - text.top).abs() <= 2.0
+            && number.left < text.left
+            && regex_subsection_number.is_match(&number.text);
+    };
+    let mut subsection_counts: Vec<((i32, bool), usize)> = Vec::new();
+    for i in 0..texts.len() {
+        let text_style = style(&texts[i]);
+        if text_style == body_style || text_style == heading_style || !is_numbered_subsection(i) {
+            continue;
+        }
+        match subsection_counts.iter_mut().find(|(key, _)| *key == text_style) {
+            Some((_, count)) => *count += 1,
+            None => subsection_counts.push((text_style, 1)),
+        }
     }
+    let subsection_style = subsection_counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count >= MIN_SUBSECTION_HEADINGS)
+        .map(|(subsection_style, _)| *subsection_style);
+
+    let is_heading = |text: &XmlText| -> bool {
+        // the headings cannot be told apart from the body text by style
+        if heading_style == body_style {
+            return text.font == heading.font;
+        }
+        if style(text) != heading_style {
+            return false;
+        }
+        if heading_style.0 > body_style.0 {
+            return true;
+        }
+        let gap = heading_style.0 as f32;
+        let inline = texts.iter().any(|other| {
+            if other.page_number != text.page_number
+                || (other.top - text.top).abs() > 2.0
+                || style(other) == heading_style
+                || regex_is_number.is_match(&other.text)
+            {
+                return false;
+            }
+            let right = text.left + text.width;
+            let other_right = other.left + other.width;
+            let follows = other.left >= right - 2.0 && other.left <= right + gap;
+            let precedes = other_right <= text.left + 2.0 && other_right >= text.left - gap;
+            return follows || precedes;
+        });
+        return !inline;
+    };
 
-    return Ok(());
+    let mut sections = Vec::new();
+    for (i, text) in texts.iter().enumerate() {
+        if text.text.is_empty() || regex_is_number.is_match(&text.text) {
+            continue;
+        }
+        let is_subsection = subsection_style == Some(style(text)) && is_numbered_subsection(i);
+        if !is_heading(text) && !is_subsection {
+            continue;
+        }
+        let title = strip_section_number(&text.text);
+        sections.push((text.page_number, title.clone()));
+        if title.to_lowercase() == "references" {
+            break;
+        }
+    }
+    return Ok(sections);
 }
 
 /// Saves the content of a PDF document as a text file using the `pdftotext` command.
@@ -350,12 +622,13 @@ fn save_pdf_as_text(
             "-htmlmeta".to_string(),
             "-bbox-layout".to_string(),
             "-r".to_string(),
-            "72".to_string(),
+            config.dpi.to_string(),
             config.pdf_path.as_str().to_string(),
             html_path.to_str().unwrap().to_string(),
         ])
         .stdout(Stdio::piped())
-        .output()?;
+        .output()
+        .map_err(|e| command_error("pdftotext", e))?;
 
     // assert that the text file exists
     let mut retry_count = 300;
@@ -363,7 +636,7 @@ fn save_pdf_as_text(
         if html_path.exists() {
             break;
         } else if retry_count == 0 {
-            return Err(Error::msg("Error: Failed to save PDF as text file"));
+            return Err(ParseError::XmlTimeout.into());
         } else {
             std::thread::sleep(Duration::from_secs(1));
             retry_count -= 1;
@@ -393,24 +666,60 @@ fn save_pdf_as_text(
 ///
 /// # Returns
 ///
-/// An `async` `Result` which is `Ok` if the PDF was successfully saved, or an `Err` if an error occurred.
+/// An `async` `Result` which is `Ok` if the PDF was successfully saved, or a `ParseError` if an error occurred.
 async fn save_pdf(
     path_or_url: &str,
     config: &mut ParserConfig,
     verbose: bool,
     time: std::time::Instant,
-) -> Result<()> {
+) -> Result<(), ParseError> {
+    download_pdf(path_or_url, config).await?;
+    convert_pdf(config, verbose, time)?;
+
+    return Ok(());
+}
+
+/// Downloads or copies a PDF document to `config.pdf_path` without converting it.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A reference to a `ParserConfig` instance containing the path to save the PDF file.
+///
+/// # Returns
+///
+/// An `async` `Result` which is `Ok` if the PDF was successfully saved, or a `ParseError` if an error occurred.
+async fn download_pdf(path_or_url: &str, config: &ParserConfig) -> Result<(), ParseError> {
     let save_path = config.pdf_path.as_str();
     if path_or_url.starts_with("http") {
-        let res = request::get(path_or_url).await;
-        let bytes = res?.bytes().await;
+        let url = config.rewrite_url(path_or_url);
+        let res = request::get(url.as_str()).await;
+        // an error page served with a 4xx or 5xx status is not a PDF document
+        let bytes = res?.error_for_status()?.bytes().await;
         let out = File::create(save_path);
         std::io::copy(&mut bytes?.as_ref(), &mut out?)?;
     } else {
         let path = Path::new(path_or_url);
-        let _ = std::fs::copy(path.as_os_str(), save_path);
+        std::fs::copy(path.as_os_str(), save_path)?;
     }
 
+    return Ok(());
+}
+
+/// Converts the PDF document saved at `config.pdf_path` into page images, XML, and HTML.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the path to the PDF file.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the PDF was successfully converted, or a `ParseError` if an error occurred.
+fn convert_pdf(
+    config: &mut ParserConfig,
+    verbose: bool,
+    time: std::time::Instant,
+) -> Result<(), ParseError> {
     // get pdf info
     get_pdf_info(config, verbose, time)?;
 
@@ -420,6 +729,9 @@ async fn save_pdf(
     // save pdf as html
     save_pdf_as_xml(config, verbose, time)?;
 
+    // make sure every page has a figure
+    reconcile_figures(config, verbose)?;
+
     // save pdf as text
     save_pdf_as_text(config, verbose, time)?;
 
@@ -443,7 +755,61 @@ async fn pdf2html(
     time: std::time::Instant,
 ) -> Result<html::Html> {
     save_pdf(path_or_url, config, verbose, time).await?;
+    return read_html(config);
+}
+
+/// The arXiv watermark at the start of a block, e.g. "arXiv:1706.03762v7 [cs.CL] 2 Aug 2023".
+static ARXIV_WATERMARK_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^arXiv:\s*(\d{4}\.\d{4,5})(v\d+)?").unwrap());
+
+/// Extracts the arXiv identifier from the watermark that arXiv adds to the margin of its PDFs,
+/// e.g. "arXiv:1706.03762v7 [cs.CL] 2 Aug 2023".
+///
+/// The watermark is printed vertically along the left margin of the first page, so only the
+/// blocks of the first page that are taller than they are wide are read; an arXiv identifier
+/// cited in the body text or the references is not taken for the watermark.
+///
+/// # Arguments
+///
+/// * `html` - The HTML document generated by `pdftotext`.
+///
+/// # Returns
+///
+/// An `Option` containing the identifier without the "arXiv:" prefix and the version,
+/// e.g. "1706.03762", or `None` if the document has no arXiv watermark.
+pub fn extract_arxiv_id(html: &html::Html) -> Option<String> {
+    let page_selector = scraper::Selector::parse("page").unwrap();
+    let block_selector = scraper::Selector::parse("block").unwrap();
+    let first_page = html.select(&page_selector).next()?;
+    for block in first_page.select(&block_selector) {
+        let attr = |key: &str| block.value().attr(key).and_then(|value| value.parse::<f32>().ok());
+        let (Some(xmin), Some(ymin), Some(xmax), Some(ymax)) =
+            (attr("xmin"), attr("ymin"), attr("xmax"), attr("ymax"))
+        else {
+            continue;
+        };
+        if xmax - xmin >= ymax - ymin {
+            continue;
+        }
+        let text = block.text().map(str::trim).filter(|text| !text.is_empty());
+        let text = text.collect::<Vec<&str>>().join(" ");
+        if let Some(caps) = ARXIV_WATERMARK_REGEX.captures(&text) {
+            return Some(caps[1].to_string());
+        }
+    }
+    return None;
+}
 
+/// Reads the HTML generated by `pdftotext` from `config.pdf_text_path`.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the path to the HTML file.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed HTML document.
+fn read_html(config: &ParserConfig) -> Result<html::Html> {
     let html_path = Path::new(config.pdf_text_path.as_str());
 
     let mut html = String::new();
@@ -459,28 +825,74 @@ async fn pdf2html(
 /// # Arguments
 ///
 /// * `image_path` - A string slice that holds the path to the image file.
-/// * `tables` - A mutable reference to a vector of `Coordinate` instances to store the table coordinates.
+/// * `tables` - A mutable reference to a vector of `DetectedTable` instances to store the detected tables.
 /// * `width` - The width of the image.
 /// * `height` - The height of the image.
-fn extract_tables(image_path: &str, tables: &mut Vec<Coordinate>, width: i32, height: i32) {
-    // read the image
-    let _src = imgcodecs::imread(image_path, imgcodecs::IMREAD_COLOR).unwrap();
-    let mut src = Mat::zeros(width, height, _src.typ()).unwrap().to_mat().unwrap();
+///
+/// If the image does not exist, or cannot be read after a few retries, e.g. because
+/// `pdftocairo` has not finished writing it, no tables are extracted for the page. Images
+/// smaller than 16 pixels in either dimension, e.g. from blank pages, and images on which an
+/// OpenCV operation fails are skipped as well.
+///
+/// # Returns
+///
+/// A `Result` which is `Err` with the reason if table extraction was skipped for the image.
+fn extract_tables(
+    image_path: &str,
+    tables: &mut Vec<DetectedTable>,
+    width: i32,
+    height: i32,
+) -> Result<()> {
+    if !Path::new(image_path).exists() {
+        return Err(Error::msg(format!("{} does not exist", image_path)));
+    }
+    // read the image, retrying while the file may still be being written
+    let mut retry_count = 3;
+    let _src = loop {
+        match imgcodecs::imread(image_path, imgcodecs::IMREAD_COLOR) {
+            Ok(image) if !image.empty() => break image,
+            _ if retry_count > 0 => {
+                std::thread::sleep(Duration::from_millis(100));
+                retry_count -= 1;
+            }
+            _ => {
+                return Err(Error::msg(format!("failed to read {}", image_path)));
+            }
+        }
+    };
+    if _src.rows() < MIN_TABLE_IMAGE_SIZE
+        || _src.cols() < MIN_TABLE_IMAGE_SIZE
+        || width < MIN_TABLE_IMAGE_SIZE
+        || height < MIN_TABLE_IMAGE_SIZE
+    {
+        return Err(Error::msg(format!(
+            "{} is too small ({}x{})",
+            image_path,
+            _src.cols(),
+            _src.rows()
+        )));
+    }
 
-    let dst_size = opencv::core::Size::new(width, height);
     // reshape
-    imgproc::resize(&_src, &mut src, dst_size, 0.0, 0.0, imgproc::INTER_LINEAR).unwrap();
+    let mut src = Mat::default();
+    let dst_size = opencv::core::Size::new(width, height);
+    if let Err(e) = imgproc::resize(&_src, &mut src, dst_size, 0.0, 0.0, imgproc::INTER_LINEAR) {
+        return Err(Error::msg(format!(
+            "failed to resize {}: {}",
+            image_path, e
+        )));
+    }
 
     // convert the image to grayscale
     let mut src_gray = Mat::default();
-    imgproc::cvt_color_def(&src, &mut src_gray, imgproc::COLOR_BGR2GRAY).unwrap();
+    imgproc::cvt_color_def(&src, &mut src_gray, imgproc::COLOR_BGR2GRAY)?;
 
     // apply Canny edge detector
     let mut edges = Mat::default();
-    imgproc::canny_def(&src_gray, &mut edges, 50.0, 200.0).unwrap();
+    imgproc::canny_def(&src_gray, &mut edges, 50.0, 200.0)?;
 
     // apply Hough Line Transform
-    let min_line_length = src.size().unwrap().width as f64 / 10.0;
+    let min_line_length = src.size()?.width as f64 / 10.0;
     let mut s_lines = Vector::<Vec4f>::new();
     imgproc::hough_lines_p(
         &edges,
@@ -490,29 +902,59 @@ fn extract_tables(image_path: &str, tables: &mut Vec<Coordinate>, width: i32, he
         100,
         min_line_length,
         3.,
-    )
-    .unwrap();
+    )?;
 
     // extract tables
-    let mut lines: Vec<(Point, Point)> = Vec::new();
-    for s_line in s_lines {
-        let [x1, y1, x2, y2] = *s_line;
+    let segments = s_lines
+        .iter()
+        .map(|s_line| {
+            let [x1, y1, x2, y2] = *s_line;
+            (Point::new(x1, y1), Point::new(x2, y2))
+        })
+        .collect::<Vec<(Point, Point)>>();
+    tables.extend(group_table_lines(&segments, src.size()?.width));
 
-        let a = (y2 - y1) / (x2 - x1);
-        if a.abs() > 1e-2 {
-            continue;
-        }
-        let len = ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt() as i32;
-        if len < src.size().unwrap().width / 4 {
-            continue;
+    return Ok(());
+}
+
+/// Groups the ruling lines detected in a page image into tables.
+///
+/// Tables are found in two ways: at least three long horizontal rules of the same length,
+/// as in booktabs-style tables, and grids of intersecting horizontal and vertical rules with
+/// at least two cells, as in bordered tables whose horizontal rules are short or few. A single
+/// frame, e.g. around a plot or an algorithm, is not a table. Both may report the same table,
+/// so overlapping regions are merged.
+///
+/// # Arguments
+///
+/// * `segments` - The line segments detected in the page image.
+/// * `width` - The width of the page image.
+///
+/// # Returns
+///
+/// A `Vec<DetectedTable>` of the detected tables.
+fn group_table_lines(segments: &[(Point, Point)], width: i32) -> Vec<DetectedTable> {
+    let mut horizontal: Vec<(Point, Point)> = Vec::new();
+    let mut vertical: Vec<(Point, Point)> = Vec::new();
+    for (p1, p2) in segments {
+        let dx = (p2.x - p1.x).abs();
+        let dy = (p2.y - p1.y).abs();
+        if dy <= dx * 1e-2 {
+            horizontal.push((p1.clone(), p2.clone()));
+        } else if dx <= dy * 1e-2 {
+            vertical.push((p1.clone(), p2.clone()));
         }
-        let line = (Point::new(x1, y1), Point::new(x2, y2));
-        lines.push(line);
     }
 
+    let mut tables: Vec<DetectedTable> = Vec::new();
+
+    // long horizontal rules of the same length
     let mut lines_gpd_by_len = HashMap::<i32, Vec<(Point, Point)>>::new();
-    for line in lines {
+    for line in horizontal.iter() {
         let mut len = ((line.0.x - line.1.x).powi(2) + (line.0.y - line.1.y).powi(2)).sqrt() as i32;
+        if len < width / 4 {
+            continue;
+        }
         for key in lines_gpd_by_len.keys() {
             if (len - key).abs() < 3 {
                 len = *key;
@@ -522,47 +964,190 @@ fn extract_tables(image_path: &str, tables: &mut Vec<Coordinate>, width: i32, he
         if !lines_gpd_by_len.contains_key(&len) {
             lines_gpd_by_len.insert(len, Vec::new());
         }
-        lines_gpd_by_len.get_mut(&len).unwrap().push(line);
+        lines_gpd_by_len.get_mut(&len).unwrap().push(line.clone());
     }
 
     for line in lines_gpd_by_len.values() {
         if line.len() < 3 {
             continue;
         }
-        let mut x_values: Vec<f32> = Vec::new();
-        let mut y_values: Vec<f32> = Vec::new();
-        for l in line {
-            x_values.push(l.0.x);
-            x_values.push(l.1.x);
-            y_values.push(l.0.y);
-            y_values.push(l.1.y);
+        let coord = lines_bounding_box(line);
+        let confidence = table_confidence(line, &coord, width as f32);
+        tables.push(DetectedTable::new(coord, confidence));
+    }
+
+    // grids of intersecting horizontal and vertical rules
+    let mut component = vec![usize::MAX; horizontal.len() + vertical.len()];
+    let mut n_components = 0;
+    for start in 0..component.len() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        component[start] = n_components;
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            for j in 0..component.len() {
+                if component[j] != usize::MAX {
+                    continue;
+                }
+                let intersects = match (i < horizontal.len(), j < horizontal.len()) {
+                    (true, false) => {
+                        lines_intersect(&horizontal[i], &vertical[j - horizontal.len()])
+                    }
+                    (false, true) => {
+                        lines_intersect(&horizontal[j], &vertical[i - horizontal.len()])
+                    }
+                    _ => false,
+                };
+                if intersects {
+                    component[j] = n_components;
+                    stack.push(j);
+                }
+            }
+        }
+        n_components += 1;
+    }
+
+    for c in 0..n_components {
+        let h_lines = horizontal
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| component[*i] == c)
+            .map(|(_, line)| line.clone())
+            .collect::<Vec<(Point, Point)>>();
+        let v_lines = vertical
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| component[horizontal.len() + *i] == c)
+            .map(|(_, line)| line.clone())
+            .collect::<Vec<(Point, Point)>>();
+        let cells = h_lines.len().saturating_sub(1) * v_lines.len().saturating_sub(1);
+        if cells < MIN_GRID_CELLS {
+            continue;
         }
-        x_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        y_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let x1 = x_values.first().unwrap().clone();
-        let x2 = x_values.last().unwrap().clone();
-        let y1 = y_values.first().unwrap().clone();
-        let y2 = y_values.last().unwrap().clone();
-        tables.push(Coordinate::from_rect(x1, y1, x2, y2));
+        let grid = h_lines.iter().chain(v_lines.iter()).cloned().collect::<Vec<(Point, Point)>>();
+        let coord = lines_bounding_box(&grid);
+        // the more cells the vertical rules cut the rows into, the more likely a table
+        let cell_score = ((cells - 1) as f32 / 4.0).clamp(0.0, 1.0);
+        let confidence = (table_confidence(&h_lines, &coord, width as f32) + cell_score) / 2.0;
+        tables.push(DetectedTable::new(coord, confidence));
     }
+
+    return merge_regions(&tables);
 }
 
-/// Computes the bounding box that contains all text areas across multiple pages.
+/// Checks whether a horizontal and a vertical line segment cross, allowing a gap of a few pixels.
 ///
 /// # Arguments
 ///
-/// * `pages` - A reference to a vector of `Page` instances.
+/// * `horizontal` - The horizontal line segment.
+/// * `vertical` - The vertical line segment.
 ///
 /// # Returns
 ///
-/// A `Coordinate` representing the bounding box that contains all text areas.
-fn get_text_area(pages: &Vec<Page>) -> Coordinate {
-    let mut left_values: Vec<f32> = Vec::new();
-    let mut right_values: Vec<f32> = Vec::new();
-    let mut top_values: Vec<f32> = Vec::new();
+/// `true` if the segments cross or touch.
+fn lines_intersect(horizontal: &(Point, Point), vertical: &(Point, Point)) -> bool {
+    let tolerance = 3.0;
+    let x = (vertical.0.x + vertical.1.x) / 2.0;
+    let y = (horizontal.0.y + horizontal.1.y) / 2.0;
+    let (left, right) = (
+        f32::min(horizontal.0.x, horizontal.1.x),
+        f32::max(horizontal.0.x, horizontal.1.x),
+    );
+    let (top, bottom) = (
+        f32::min(vertical.0.y, vertical.1.y),
+        f32::max(vertical.0.y, vertical.1.y),
+    );
+    return left - tolerance <= x
+        && x <= right + tolerance
+        && top - tolerance <= y
+        && y <= bottom + tolerance;
+}
+
+/// Computes the bounding box of line segments.
+///
+/// # Arguments
+///
+/// * `lines` - The line segments; must not be empty.
+///
+/// # Returns
+///
+/// A `Coordinate` that contains all end points of the segments.
+fn lines_bounding_box(lines: &[(Point, Point)]) -> Coordinate {
+    let mut x_values: Vec<f32> = Vec::new();
+    let mut y_values: Vec<f32> = Vec::new();
+    for l in lines {
+        x_values.push(l.0.x);
+        x_values.push(l.1.x);
+        y_values.push(l.0.y);
+        y_values.push(l.1.y);
+    }
+    x_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    y_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let x1 = x_values.first().unwrap().clone();
+    let x2 = x_values.last().unwrap().clone();
+    let y1 = y_values.first().unwrap().clone();
+    let y2 = y_values.last().unwrap().clone();
+    return Coordinate::from_rect(x1, y1, x2, y2);
+}
+
+/// Scores how table-like a group of horizontal ruling lines is.
+///
+/// The score is the mean of three values from 0.0 to 1.0: the number of lines (saturating
+/// at seven), the mean line length relative to half the page width, and the aspect ratio
+/// of the region (regions flatter than 1:20 are penalized, e.g. a double rule).
+///
+/// # Arguments
+///
+/// * `lines` - The ruling lines of the region.
+/// * `coord` - The bounding box of the lines.
+/// * `page_width` - The width of the page image.
+///
+/// # Returns
+///
+/// An `f32` from 0.0 to 1.0.
+fn table_confidence(lines: &[(Point, Point)], coord: &Coordinate, page_width: f32) -> f32 {
+    if lines.is_empty() || coord.width() <= 0.0 || page_width <= 0.0 {
+        return 0.0;
+    }
+    let line_score = ((lines.len() as f32 - 2.0) / 5.0).clamp(0.0, 1.0);
+    let total_length = lines
+        .iter()
+        .map(|(p1, p2)| ((p1.x - p2.x).powi(2) + (p1.y - p2.y).powi(2)).sqrt())
+        .sum::<f32>();
+    let mean_length = total_length / lines.len() as f32;
+    let length_score = (mean_length / (page_width / 2.0)).clamp(0.0, 1.0);
+    let aspect_score = (coord.height() / coord.width() / 0.05).clamp(0.0, 1.0);
+    return (line_score + length_score + aspect_score) / 3.0;
+}
+
+/// Computes the bounding box that contains all text areas across multiple pages.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `Coordinate` representing the bounding box that contains all text areas.
+/// If no page has any line, the whole page area is returned.
+fn get_text_area(pages: &Vec<Page>) -> Coordinate {
+    let mut left_values: Vec<f32> = Vec::new();
+    let mut right_values: Vec<f32> = Vec::new();
+    let mut top_values: Vec<f32> = Vec::new();
     let mut bottom_values: Vec<f32> = Vec::new();
 
-    for page in pages {
+    let pages_with_lines = pages
+        .iter()
+        .filter(|page| page.blocks.iter().any(|block| !block.lines.is_empty()))
+        .collect::<Vec<&Page>>();
+    if pages_with_lines.is_empty() {
+        let width = pages.iter().map(|page| page.width).fold(0.0, f32::max);
+        let height = pages.iter().map(|page| page.height).fold(0.0, f32::max);
+        return Coordinate::from_rect(0.0, 0.0, width, height);
+    }
+
+    for page in pages_with_lines {
         left_values.push(page.left());
         right_values.push(page.right());
         top_values.push(page.top());
@@ -587,16 +1172,27 @@ fn get_text_area(pages: &Vec<Page>) -> Coordinate {
 
 /// Adjusts the columns of text in the PDF pages based on the page width and configuration.
 ///
+/// Line widths are measured relative to the width of their own page, so documents that mix
+/// page sizes (e.g. a landscape table page in a portrait paper) are handled per page.
+/// When the lines are narrower than two thirds of the page, the number of columns is
+/// estimated with `estimate_columns`, at most the page width divided by the average line
+/// width, and the blocks of each page are reordered column by column, from top to bottom
+/// within a column.
+///
 /// # Arguments
 ///
 /// * `pages` - A mutable reference to a vector of `Page` instances.
 /// * `config` - A reference to a `ParserConfig` instance containing the configuration for the adjustment.
 fn adjst_columns(pages: &mut Vec<Page>, config: &ParserConfig) {
-    let page_width = config.pdf_info.get("page_width").unwrap().parse::<f32>().unwrap();
-    let last_page = config.sections.iter().map(|(page_number, _)| page_number).max().unwrap();
-    let avg_line_width = pages
+    let last_page = config
+        .sections
         .iter()
-        .filter(|page| page.page_nubmer <= *last_page)
+        .map(|(page_number, _)| *page_number)
+        .max()
+        .unwrap_or(PageNumber::MAX);
+    let avg_line_ratio = pages
+        .iter()
+        .filter(|page| page.page_nubmer <= last_page)
         .map(|page| {
             page.blocks
                 .iter()
@@ -606,48 +1202,379 @@ fn adjst_columns(pages: &mut Vec<Page>, config: &ParserConfig) {
                 })
                 .sum::<f32>()
                 / page.blocks.len() as f32
+                / page.width
         })
         .sum::<f32>()
         / pages.len() as f32;
 
-    let half_width = page_width / 2.2;
-    if avg_line_width < page_width / 1.5 {
-        // Tow Columns
+    if avg_line_ratio < 1.0 / 1.5 {
+        let body_pages = pages
+            .iter()
+            .filter(|page| page.page_nubmer <= last_page)
+            .cloned()
+            .collect::<Vec<Page>>();
+        // lines cannot be wider than their column, so the line width bounds the estimate
+        let max_columns = ((1.0 / avg_line_ratio) as i8).max(2);
+        let number_of_columns = estimate_columns(&body_pages).clamp(2, max_columns);
         for page in pages.iter_mut() {
-            page.number_of_columns = 2;
-            let mut right_blocks: Vec<Block> = Vec::new();
-            let mut left_blocks: Vec<Block> = Vec::new();
-            for block in page.blocks.iter() {
-                if half_width < block.x {
-                    right_blocks.push(block.clone());
-                } else {
-                    left_blocks.push(block.clone());
-                }
+            page.number_of_columns = number_of_columns;
+            // a block belongs to the last column whose left boundary it starts after
+            let column_width = page.width / (1.1 * number_of_columns as f32);
+            let column =
+                |block: &Block| ((block.x / column_width) as i8).clamp(0, number_of_columns - 1);
+            // the XML does not always emit the blocks of a column from top to bottom
+            page.blocks
+                .sort_by(|a, b| column(a).cmp(&column(b)).then(a.y.partial_cmp(&b.y).unwrap()));
+        }
+    }
+}
+
+/// Estimates the number of text columns from the left edges of the blocks.
+///
+/// The left edges of blocks with at least two lines, relative to their page width, are
+/// clustered wherever two neighboring edges are more than 10% of the page width apart.
+/// Clusters holding fewer than 10% of the edges, e.g. centered equations or indented
+/// quotes, are ignored, and each remaining cluster is a column.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// The estimated number of columns, at least 1.
+fn estimate_columns(pages: &Vec<Page>) -> i8 {
+    let mut edges = pages
+        .iter()
+        .flat_map(|page| {
+            page.blocks
+                .iter()
+                .filter(|block| block.lines.len() >= 2)
+                .map(move |block| block.x / page.width)
+        })
+        .collect::<Vec<f32>>();
+    if edges.is_empty() {
+        return 1;
+    }
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut clusters: Vec<usize> = vec![1];
+    for pair in edges.windows(2) {
+        if pair[1] - pair[0] > 0.1 {
+            clusters.push(1);
+        } else {
+            *clusters.last_mut().unwrap() += 1;
+        }
+    }
+    let min_size = edges.len() as f32 * 0.1;
+    let columns = clusters.iter().filter(|size| **size as f32 >= min_size).count();
+    return columns.clamp(1, i8::MAX as usize) as i8;
+}
+
+/// Merges overlapping regions detected on a page.
+///
+/// Table detection groups ruling lines by length, so a single table may be reported more
+/// than once with slightly different boxes. Regions whose IoU is above 0.5 are merged into
+/// their bounding box with the higher confidence of the two. Figure regions are not detected
+/// yet, so only `Page::tables` is reconciled.
+///
+/// # Arguments
+///
+/// * `page` - A mutable reference to the `Page` whose regions are merged.
+pub fn dedupe_regions(page: &mut Page) {
+    page.tables = merge_regions(&page.tables);
+}
+
+/// Merges regions whose IoU is above 0.5 into their bounding box with the higher confidence.
+///
+/// # Arguments
+///
+/// * `tables` - The regions to merge.
+///
+/// # Returns
+///
+/// A `Vec<DetectedTable>` of the merged regions.
+fn merge_regions(tables: &[DetectedTable]) -> Vec<DetectedTable> {
+    let mut regions: Vec<DetectedTable> = Vec::new();
+    for table in tables.iter() {
+        match regions
+            .iter_mut()
+            .find(|region| region.coord.iou(&table.coord) > REGION_IOU_THRESHOLD)
+        {
+            Some(region) => {
+                region.coord = Coordinate::from_rect(
+                    f32::min(region.coord.top_left.x, table.coord.top_left.x),
+                    f32::min(region.coord.top_left.y, table.coord.top_left.y),
+                    f32::max(region.coord.bottom_right.x, table.coord.bottom_right.x),
+                    f32::max(region.coord.bottom_right.y, table.coord.bottom_right.y),
+                );
+                region.confidence = f32::max(region.confidence, table.confidence);
+            }
+            None => regions.push(table.clone()),
+        }
+    }
+    return regions;
+}
+
+/// Removes regions that repeat at the same position on many pages.
+///
+/// Logos, letterheads, and watermarks are drawn at the same place on every page and are
+/// picked up by table detection as a region per page. A region is dropped when a region
+/// with an IoU above 0.9 appears on at least three pages and on at least half of all pages.
+///
+/// # Arguments
+///
+/// * `tables` - A mutable reference to the detected regions of each page, in document order.
+pub fn remove_repeated_regions(tables: &mut Vec<Vec<DetectedTable>>) {
+    let min_pages = usize::max(MIN_REPEATED_REGION_PAGES, (tables.len() + 1) / 2);
+    let repeated = tables
+        .iter()
+        .map(|regions| {
+            regions
+                .iter()
+                .map(|region| {
+                    let pages = tables
+                        .iter()
+                        .filter(|others| {
+                            others.iter().any(|other| {
+                                region.coord.iou(&other.coord) > REPEATED_REGION_IOU_THRESHOLD
+                            })
+                        })
+                        .count();
+                    pages >= min_pages
+                })
+                .collect::<Vec<bool>>()
+        })
+        .collect::<Vec<Vec<bool>>>();
+
+    for (regions, repeated) in tables.iter_mut().zip(repeated) {
+        let mut flags = repeated.into_iter();
+        regions.retain(|_| !flags.next().unwrap_or(false));
+    }
+}
+
+/// Removes running headers and footers, e.g. the journal name or "Preprint. Under review.",
+/// from every page.
+///
+/// A block of at most two lines in the top or bottom margin of a page is a running header when
+/// a block with the same text appears at nearly the same y-position on at least three pages.
+/// Texts are compared after `normalize_for_matching` with every number masked, so page numbers
+/// such as "Page 3" and "Page 12" are treated as the same text. Blocks between the margins,
+/// e.g. equation numbers such as "(3)" or short table rows, are never removed.
+///
+/// # Arguments
+///
+/// * `pages` - A mutable reference to a vector of `Page` instances.
+pub fn strip_running_headers(pages: &mut Vec<Page>) {
+    let number_regex = regex::Regex::new(r"\d+").unwrap();
+
+    // the page index, block index, and relative y-position of the margin blocks by their text
+    let mut candidates: HashMap<String, Vec<(usize, usize, f32)>> = HashMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        let height = page.height.max(1.0);
+        for (j, block) in page.blocks.iter().enumerate() {
+            let top = block.y / height;
+            let bottom = (block.y + block.height) / height;
+            let in_margin =
+                top <= RUNNING_HEADER_MARGIN_RATIO || bottom >= 1.0 - RUNNING_HEADER_MARGIN_RATIO;
+            if block.lines.len() > 2 || !in_margin {
+                continue;
+            }
+            let text = normalize_for_matching(&block.get_text());
+            if text.is_empty() {
+                continue;
+            }
+            let text = number_regex.replace_all(&text, "#").to_string();
+            candidates.entry(text).or_default().push((i, j, top));
+        }
+    }
+
+    let mut repeated: HashSet<(usize, usize)> = HashSet::new();
+    for blocks in candidates.values() {
+        if blocks.len() < MIN_RUNNING_HEADER_PAGES {
+            continue;
+        }
+        for (i, j, y) in blocks.iter() {
+            let pages = blocks
+                .iter()
+                .filter(|(_, _, other_y)| (other_y - y).abs() <= RUNNING_HEADER_Y_TOLERANCE)
+                .map(|(other_i, _, _)| *other_i)
+                .collect::<HashSet<usize>>();
+            if pages.len() >= MIN_RUNNING_HEADER_PAGES {
+                repeated.insert((*i, *j));
+            }
+        }
+    }
+
+    for (i, page) in pages.iter_mut().enumerate() {
+        let mut j = 0;
+        page.blocks.retain(|_| {
+            j += 1;
+            return !repeated.contains(&(i, j - 1));
+        });
+    }
+}
+
+/// Removes the line numbers printed in the margins of review copies, e.g. "1", "2", "3" down the
+/// left edge of each page.
+///
+/// A block is a line number block when each of its lines is a single integer and it lies
+/// entirely left or right of the text area of the other blocks. The line numbers on each side
+/// of a page are only removed when there are at least five of them and they increase from the
+/// top of the page to the bottom, so numbered lists inside the text area are never affected.
+///
+/// # Arguments
+///
+/// * `pages` - A mutable reference to a vector of `Page` instances.
+pub fn remove_margin_line_numbers(pages: &mut Vec<Page>) {
+    let number_regex = regex::Regex::new(r"^\d{1,5}$").unwrap();
+    let is_number_block = |block: &Block| {
+        return !block.lines.is_empty()
+            && block.lines.iter().all(|line| number_regex.is_match(line.get_text().trim()));
+    };
+
+    // the text area is computed without the number blocks, which would widen it
+    let mut left_values: Vec<f32> = Vec::new();
+    let mut right_values: Vec<f32> = Vec::new();
+    for page in pages.iter() {
+        let blocks = page.blocks.iter().filter(|block| !is_number_block(block));
+        let (left, right) = blocks.fold((f32::MAX, f32::MIN), |(left, right), block| {
+            (left.min(block.x), right.max(block.x + block.width))
+        });
+        if left <= right {
+            left_values.push(left);
+            right_values.push(right);
+        }
+    }
+    if left_values.is_empty() {
+        return;
+    }
+    let left = sci_rs::stats::median(left_values.iter()).0;
+    let right = sci_rs::stats::median(right_values.iter()).0;
+
+    for page in pages.iter_mut() {
+        let mut remove_indices: Vec<usize> = Vec::new();
+        for in_left_margin in [true, false] {
+            let indices = page
+                .blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, block)| is_number_block(block))
+                .filter(|(_, block)| match in_left_margin {
+                    true => block.x + block.width <= left,
+                    false => block.x >= right,
+                })
+                .map(|(i, _)| i)
+                .collect::<Vec<usize>>();
+            let mut numbers = indices
+                .iter()
+                .flat_map(|i| page.blocks[*i].lines.iter())
+                .map(|line| (line.y, line.get_text().trim().parse::<u64>().unwrap_or(0)))
+                .collect::<Vec<(f32, u64)>>();
+            numbers.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let increasing = numbers.windows(2).all(|pair| pair[0].1 < pair[1].1);
+            if numbers.len() >= MIN_MARGIN_LINE_NUMBERS && increasing {
+                remove_indices.extend(indices);
             }
-            left_blocks.append(&mut right_blocks);
-            page.blocks = left_blocks;
         }
+        remove_indices.sort();
+        for i in remove_indices.iter().rev() {
+            page.blocks.remove(*i);
+        }
+    }
+}
+
+/// Detects the tables of every page in parallel.
+///
+/// Pages on which table detection is skipped, e.g. because the page image cannot be read,
+/// are reported as `ParseDiagnostic::TablesSkipped` in `config.diagnostics`.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the page images.
+/// * `page_sizes` - The number, width, and height of each page in document order.
+/// * `table_start_page` - The first page on which tables are detected.
+///
+/// # Returns
+///
+/// A vector containing the tables of each page, in the same order as `page_sizes`.
+/// Tables with a confidence below `config.min_table_confidence` are discarded.
+fn detect_tables(
+    config: &mut ParserConfig,
+    page_sizes: &Vec<(PageNumber, f32, f32)>,
+    table_start_page: PageNumber,
+) -> Vec<Vec<DetectedTable>> {
+    let results = page_sizes
+        .par_iter()
+        .map(|(page_number, width, height)| {
+            let mut tables = Vec::new();
+            let mut skipped = None;
+            if table_start_page <= *page_number {
+                let fig_path = config.pdf_figures.get(page_number).unwrap();
+                if let Err(e) = extract_tables(fig_path, &mut tables, *width as i32, *height as i32)
+                {
+                    skipped = Some(ParseDiagnostic::TablesSkipped {
+                        page: *page_number,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+            tables.retain(|table| table.confidence >= config.min_table_confidence);
+            (tables, skipped)
+        })
+        .collect::<Vec<(Vec<DetectedTable>, Option<ParseDiagnostic>)>>();
+
+    let mut page_tables = Vec::new();
+    for (tables, skipped) in results {
+        config.diagnostics.extend(skipped);
+        page_tables.push(tables);
     }
+    return page_tables;
 }
 
 fn parse_html2pages(config: &mut ParserConfig, html: html::Html) -> Result<Vec<Page>> {
+    return parse_html2pages_with_tables_from(config, html, 1);
+}
+
+/// Parses the pdftotext HTML into pages, running table detection only from `table_start_page` onward.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the extracted figures.
+/// * `html` - The HTML document generated by `pdftotext`.
+/// * `table_start_page` - The first page on which tables are detected.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `Page` instances.
+fn parse_html2pages_with_tables_from(
+    config: &mut ParserConfig,
+    html: html::Html,
+    table_start_page: PageNumber,
+) -> Result<Vec<Page>> {
     let mut pages = Vec::new();
     let page_selector = scraper::Selector::parse("page").unwrap();
+    // pdftotext scales the boxes by dpi / 72 but reports the page size in points
+    let zoom = config.render_zoom();
+    let page_sizes = html
+        .select(&page_selector)
+        .enumerate()
+        .map(|(_page_number, page)| {
+            let page_number = (_page_number + 1) as PageNumber;
+            let page_width = page.value().attr("width").unwrap().parse::<f32>().unwrap() * zoom;
+            let page_height = page.value().attr("height").unwrap().parse::<f32>().unwrap() * zoom;
+            (page_number, page_width, page_height)
+        })
+        .collect::<Vec<(PageNumber, f32, f32)>>();
+    let mut tables = detect_tables(config, &page_sizes, table_start_page);
+    remove_repeated_regions(&mut tables);
+    let mut tables = tables.into_iter();
+
     let _pages = html.select(&page_selector);
-    for (_page_number, page) in _pages.enumerate() {
-        let page_number = (_page_number + 1) as PageNumber;
-        let page_width = page.value().attr("width").unwrap().parse::<f32>().unwrap();
-        let page_height = page.value().attr("height").unwrap().parse::<f32>().unwrap();
+    for (page, (page_number, page_width, page_height)) in _pages.zip(page_sizes) {
         let mut _page = Page::new(page_width, page_height, page_number);
-
-        // extract tables
-        let fig_path = config.pdf_figures.get(&page_number).unwrap();
-        extract_tables(
-            fig_path,
-            &mut _page.tables,
-            _page.width as i32,
-            _page.height as i32,
-        );
+        _page.tables = tables.next().unwrap_or_default();
+        dedupe_regions(&mut _page);
 
         let block_selector = scraper::Selector::parse("block").unwrap();
         let _blocks = page.select(&block_selector);
@@ -665,7 +1592,7 @@ fn parse_html2pages(config: &mut ParserConfig, html: html::Html) -> Result<Vec<P
 
             let line_selector = scraper::Selector::parse("line").unwrap();
             let _lines = block.select(&line_selector);
-            'line_iter: for line in _lines {
+            for line in _lines {
                 let line_xmin = line.value().attr("xmin").unwrap().parse::<f32>().unwrap();
                 let line_ymin = line.value().attr("ymin").unwrap().parse::<f32>().unwrap();
                 let line_xmax = line.value().attr("xmax").unwrap().parse::<f32>().unwrap();
@@ -677,14 +1604,6 @@ fn parse_html2pages(config: &mut ParserConfig, html: html::Html) -> Result<Vec<P
                     line_ymax - line_ymin,
                 );
 
-                for table in _page.tables.iter() {
-                    let line_coord =
-                        Coordinate::from_object(_line.x, _line.y, _line.width, _line.height);
-                    if line_coord.is_contained_in(&table) {
-                        continue 'line_iter;
-                    }
-                }
-
                 let word_selector = scraper::Selector::parse("word").unwrap();
                 let _words = line.select(&word_selector);
                 for word in _words {
@@ -693,22 +1612,23 @@ fn parse_html2pages(config: &mut ParserConfig, html: html::Html) -> Result<Vec<P
                     let word_xmax = word.value().attr("xmax").unwrap().parse::<f32>().unwrap();
                     let word_ymax = word.value().attr("ymax").unwrap().parse::<f32>().unwrap();
                     let text = word.text().collect::<String>();
+                    // pdftotext occasionally emits words with xmax == xmin
                     _line.add_word(
                         text.clone(),
                         word_xmin,
                         word_ymin,
-                        word_xmax - word_xmin,
-                        word_ymax - word_ymin,
+                        (word_xmax - word_xmin).max(MIN_WORD_SIZE),
+                        (word_ymax - word_ymin).max(MIN_WORD_SIZE),
                     );
                 }
-                if _line.get_text().trim().len() > 0 {
-                    _block.lines.push(_line);
+                if config.fix_char_spacing {
+                    _line.fix_char_spacing();
                 }
+                _block.lines.push(_line);
             }
-            if _block.lines.len() > 0 {
-                _page.blocks.push(_block);
-            }
+            _page.blocks.push(_block);
         }
+        collect_table_lines(&mut _page, config.table_containment_threshold);
         if _page.blocks.len() > 0 {
             pages.push(_page);
         }
@@ -716,28 +1636,207 @@ fn parse_html2pages(config: &mut ParserConfig, html: html::Html) -> Result<Vec<P
     return Ok(pages);
 }
 
+/// Moves the lines inside the tables of a page into the table cells and drops empty lines and blocks.
+///
+/// Lines inside a table are kept as its cells rather than as body text, see `table_rows`.
+/// Tables that contain no lines, e.g. a frame around an image, are dropped.
+///
+/// # Arguments
+///
+/// * `page` - A mutable reference to a `Page` built from the `pdftotext` output.
+/// * `containment_threshold` - The fraction of a line that must lie inside a table to be a cell of it.
+fn collect_table_lines(page: &mut Page, containment_threshold: f32) {
+    let mut table_lines: Vec<Vec<Line>> = vec![Vec::new(); page.tables.len()];
+    for block in page.blocks.iter_mut() {
+        let lines = std::mem::take(&mut block.lines);
+        for line in lines {
+            let line_coord = Coordinate::from_object(line.x, line.y, line.width, line.height);
+            let table_index = page.tables.iter().position(|table| {
+                line_coord.is_contained_in_with_threshold(&table.coord, containment_threshold)
+            });
+            if let Some(index) = table_index {
+                table_lines[index].push(line);
+            } else if line.get_text().trim().len() > 0 {
+                block.lines.push(line);
+            }
+        }
+    }
+    page.blocks.retain(|block| block.lines.len() > 0);
+    for (table, lines) in page.tables.iter_mut().zip(table_lines.iter()) {
+        table.rows = table_rows(lines);
+    }
+    let mut table_lines = table_lines.iter();
+    page.tables.retain(|_| table_lines.next().map_or(false, |lines| !lines.is_empty()));
+}
+
+/// Arranges the lines found inside a table into rows and columns of cell texts.
+///
+/// Lines whose vertical centers fall within the same band form a row, since `pdftotext`
+/// often splits a row into one line per cell. Within a row, words separated by a gap wider
+/// than 60% of the median word height start a new cell. The row with the most cells defines
+/// the columns, and every other cell is placed in the column it overlaps most, so a header
+/// cell spanning several columns is placed in one of them.
+///
+/// # Arguments
+///
+/// * `lines` - The lines inside the table region.
+///
+/// # Returns
+///
+/// A `Vec<Vec<String>>` of cell texts, row by row, with one entry per column.
+fn table_rows(lines: &Vec<Line>) -> Vec<Vec<String>> {
+    let mut sorted = lines.iter().filter(|line| !line.words.is_empty()).collect::<Vec<&Line>>();
+    if sorted.is_empty() {
+        return Vec::new();
+    }
+    sorted.sort_by(|a, b| (a.y + a.height / 2.0).partial_cmp(&(b.y + b.height / 2.0)).unwrap());
+
+    // rows
+    let mut rows: Vec<(f32, f32, Vec<&Word>)> = Vec::new();
+    for line in sorted {
+        let center = line.y + line.height / 2.0;
+        match rows.last_mut() {
+            Some((top, bottom, words)) if *top <= center && center <= *bottom => {
+                *top = f32::min(*top, line.y);
+                *bottom = f32::max(*bottom, line.y + line.height);
+                words.extend(line.words.iter());
+            }
+            _ => rows.push((line.y, line.y + line.height, line.words.iter().collect())),
+        }
+    }
+
+    // cells
+    let mut heights = rows
+        .iter()
+        .flat_map(|(_, _, words)| words.iter().map(|word| word.height))
+        .collect::<Vec<f32>>();
+    heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let min_gap = heights[heights.len() / 2] * 0.6;
+    let mut row_cells: Vec<Vec<(f32, f32, String)>> = Vec::new();
+    for (_, _, mut words) in rows {
+        words.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+        let mut cells: Vec<(f32, f32, String)> = Vec::new();
+        for word in words {
+            match cells.last_mut() {
+                Some((_, right, text)) if word.x - *right <= min_gap => {
+                    *right = f32::max(*right, word.x + word.width);
+                    text.push(' ');
+                    text.push_str(&word.text);
+                }
+                _ => cells.push((word.x, word.x + word.width, word.text.clone())),
+            }
+        }
+        row_cells.push(cells);
+    }
+
+    // columns
+    let template = row_cells.iter().fold(&row_cells[0], |widest, cells| {
+        if cells.len() > widest.len() {
+            cells
+        } else {
+            widest
+        }
+    });
+    let columns =
+        template.iter().map(|(left, right, _)| (*left, *right)).collect::<Vec<(f32, f32)>>();
+    let mut table = Vec::new();
+    for cells in row_cells {
+        let mut row = vec![String::new(); columns.len()];
+        for (left, right, text) in cells {
+            // the overlap is negative for columns to either side, so the nearest column wins
+            let overlap = |column: usize| {
+                f32::min(right, columns[column].1) - f32::max(left, columns[column].0)
+            };
+            let column = (0..columns.len())
+                .max_by(|&a, &b| overlap(a).partial_cmp(&overlap(b)).unwrap().then(b.cmp(&a)))
+                .unwrap();
+            if !row[column].is_empty() {
+                row[column].push(' ');
+            }
+            row[column].push_str(&text);
+        }
+        table.push(row);
+    }
+    return table;
+}
+
+/// Normalizes a heading so that detected titles and body headings can be compared.
+///
+/// The section number is stripped, the text is lowercased, and runs of whitespace are
+/// collapsed, so "3  Method", "3. Method", and "method" are all the same heading.
+///
+/// # Arguments
+///
+/// * `text` - The heading text.
+///
+/// # Returns
+///
+/// A `String` containing the normalized heading.
+fn normalize_heading(text: &str) -> String {
+    let text = strip_section_number(text).to_lowercase();
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
 fn parse_extract_textarea(config: &mut ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
-    let section_titles =
-        config.sections.iter().map(|(_, section)| section.to_lowercase()).collect::<Vec<String>>();
+    remove_margin_line_numbers(pages);
+
+    let section_titles = config
+        .sections
+        .iter()
+        .map(|(_, section)| normalize_heading(section))
+        .collect::<Vec<String>>();
     let text_area = get_text_area(&pages);
-    let title_index_regex = regex::Regex::new(r"\d+\.").unwrap();
+
+    // without detected sections, blocks set in a larger font than the body are kept as headings
+    let heading_font_size = if section_titles.is_empty() {
+        let font_sizes = pages
+            .iter()
+            .flat_map(|page| page.blocks.iter())
+            .flat_map(|block| block.lines.iter())
+            .flat_map(|line| line.words.iter())
+            .map(|word| word.font_size())
+            .collect::<Vec<f32>>();
+        let body_font_size: f32 = sci_rs::stats::median(font_sizes.iter()).0;
+        Some(body_font_size * 1.15)
+    } else {
+        None
+    };
+
     for page in pages.iter_mut() {
         let mut remove_indices: Vec<usize> = Vec::new();
-        let width = if page.number_of_columns == 2 {
-            page.width / 2.2
-        } else {
-            page.width / 1.1
-        };
+        let width = page.width / (1.1 * page.number_of_columns.max(1) as f32);
+        // blocks of the abstract on the first page are never pruned as narrow blocks
+        let mut in_abstract = false;
         for (i, block) in page.blocks.iter_mut().enumerate() {
             let block_coord = Coordinate::from_object(block.x, block.y, block.width, block.height);
             let iou = text_area.iou(&block_coord);
             let block_text = block.get_text();
-            let block_text = title_index_regex.replace(&block_text, "").trim().to_string();
+            let block_text = strip_section_number(&block_text);
+            let is_title = match heading_font_size {
+                Some(font_size) => {
+                    block.lines.len() <= 2
+                        && block
+                            .lines
+                            .iter()
+                            .flat_map(|line| line.words.iter())
+                            .any(|word| word.font_size() > font_size)
+                }
+                None => section_titles.contains(&normalize_heading(&block_text)),
+            };
+            if page.page_nubmer == 1 && is_title {
+                in_abstract = block_text.to_lowercase() == "abstract";
+            }
+            let is_abstract = page.page_nubmer == 1
+                && (in_abstract || block_text.to_lowercase().starts_with("abstract"));
 
-            if (iou - 0.0).abs() < 1e-6 {
+            // section titles are never pruned, e.g. a heading at the bottom of a column
+            if is_title {
+                continue;
+            } else if (iou - 0.0).abs() < 1e-6 {
                 remove_indices.push(i);
-            } else if !section_titles.contains(&block_text.to_lowercase())
-                && (block.width / width < 0.3 && block.lines.len() < 4)
+            } else if !is_abstract
+                && (block.width / width < config.min_block_width_ratio
+                    && block.lines.len() < config.min_block_lines)
             {
                 remove_indices.push(i);
             }
@@ -749,46 +1848,425 @@ fn parse_extract_textarea(config: &mut ParserConfig, pages: &mut Vec<Page>) -> R
     return Ok(());
 }
 
+/// Removes an inline "Abstract—" or "Abstract:" heading from the start of a line.
+///
+/// # Arguments
+///
+/// * `line` - A mutable reference to the `Line` to split.
+///
+/// # Returns
+///
+/// A `bool` indicating whether the line started with an inline abstract heading.
+fn split_inline_abstract(line: &mut Line) -> bool {
+    let abstract_regex = regex::Regex::new(r"^(?i)abstract\s*(—|–|--?|:|\.)\s*").unwrap();
+    let text = line.get_text();
+    let prefix_len = match abstract_regex.find(&text) {
+        Some(m) => m.end(),
+        None => return false,
+    };
+
+    // words are joined by single spaces in `Line::get_text`
+    let mut remaining = prefix_len;
+    while remaining > 0 && !line.words.is_empty() {
+        let word_len = line.words[0].text.len();
+        if word_len <= remaining {
+            remaining = remaining.saturating_sub(word_len + 1);
+            line.words.remove(0);
+        } else {
+            line.words[0].text = line.words[0].text[remaining..].to_string();
+            remaining = 0;
+        }
+    }
+    return true;
+}
+
 fn parse_extract_secsions(config: &mut ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
     let mut current_section = "Abstract".to_string();
-    let mut page_number = 1;
-    let title_regex = regex::Regex::new(r"\d+\.").unwrap();
+    let mut found_inline_abstract = false;
     for page in pages.iter_mut() {
-        for block in page.blocks.iter_mut() {
-            for line in block.lines.iter_mut() {
-                let text = line.get_text();
-                let text = title_regex.replace(&text, "").trim().to_string();
-                if config.sections.iter().any(|(pg, section)| {
-                    text.to_lowercase() == *section.to_lowercase() && pg == &page_number
-                }) {
-                    current_section = text;
-                }
-                block.section = current_section.clone();
-            }
-        }
-        page_number += 1;
+        assign_page_sections(
+            config,
+            page,
+            &mut current_section,
+            &mut found_inline_abstract,
+        );
     }
     return Ok(());
 }
-/// Parses a PDF document from a given URL or local path and extracts its pages.
+
+/// Sets the section of each block of a page, continuing from the section of the previous page.
 ///
 /// # Arguments
 ///
-/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
-/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
-///
-/// # Returns
-///
-/// An `async` `Result` containing a vector of `Page` instances if the parsing was successful, or an `Err` if an error occurred.
+/// * `config` - A reference to a `ParserConfig` instance containing the detected section titles.
+/// * `page` - A mutable reference to the `Page` whose blocks are assigned.
+/// * `current_section` - The section at the end of the previous page, updated to the section at the end of this page.
+/// * `found_inline_abstract` - Whether an inline "Abstract" heading has been split off, updated for this page.
+fn assign_page_sections(
+    config: &ParserConfig,
+    page: &mut Page,
+    current_section: &mut String,
+    found_inline_abstract: &mut bool,
+) {
+    let page_number = page.page_nubmer;
+    for block in page.blocks.iter_mut() {
+        for line in block.lines.iter_mut() {
+            if page_number == 1 && !*found_inline_abstract && split_inline_abstract(line) {
+                *found_inline_abstract = true;
+                *current_section = "Abstract".to_string();
+            }
+            let text = line.get_text();
+            let heading = normalize_heading(&text);
+            if config
+                .sections
+                .iter()
+                .any(|(pg, section)| heading == normalize_heading(section) && pg == &page_number)
+            {
+                *current_section = strip_section_number(&text);
+            }
+            block.section = current_section.clone();
+        }
+    }
+}
+
+/// Sets the `BlockType` of every block.
+///
+/// Headers are the detected section titles and the blocks that look like a heading on their
+/// own, e.g. numbered subsections, see `Block::looks_like_heading`. Footnotes are lines set smaller than the body text in the bottom 30% of a page, the first
+/// of which starts with a footnote marker, see `Line::starts_with_footnote_marker`. Footnote
+/// lines that `pdftotext` merged into the end of a body block are split into a block of
+/// their own first, starting at the first marked line.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the detected sections.
+/// * `pages` - A mutable reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the blocks were classified.
+fn parse_classify_blocks(config: &mut ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
+    let font_sizes = pages
+        .iter()
+        .flat_map(|page| page.blocks.iter())
+        .flat_map(|block| block.lines.iter())
+        .flat_map(|line| line.words.iter())
+        .map(|word| word.font_size())
+        .collect::<Vec<f32>>();
+    let body_font_size = median_font_size(&font_sizes);
+    for page in pages.iter_mut() {
+        classify_page_blocks(config, page, body_font_size);
+    }
+    return Ok(());
+}
+
+/// Returns the median of the given font sizes, or `0.0` if there are none.
+fn median_font_size(font_sizes: &Vec<f32>) -> f32 {
+    if font_sizes.is_empty() {
+        return 0.0;
+    }
+    return sci_rs::stats::median(font_sizes.iter()).0;
+}
+
+/// Classifies the blocks of a single page, see `parse_classify_blocks`.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the detected sections.
+/// * `page` - A mutable reference to the `Page` whose blocks are classified.
+/// * `body_font_size` - The median font size of the words of the whole document.
+fn classify_page_blocks(config: &ParserConfig, page: &mut Page, body_font_size: f32) {
+    let section_titles = config
+        .sections
+        .iter()
+        .map(|(_, section)| normalize_heading(section))
+        .collect::<Vec<String>>();
+    let is_footnote_line = |line: &Line, page_height: f32| -> bool {
+        if line.words.is_empty() || line.y < page_height * FOOTNOTE_TOP_RATIO {
+            return false;
+        }
+        let font_size = line.words.iter().map(|word| word.font_size()).fold(0.0, f32::max);
+        return font_size < body_font_size * FOOTNOTE_FONT_RATIO;
+    };
+
+    let mut blocks = Vec::new();
+    for mut block in page.blocks.drain(..) {
+        let small_lines =
+            block.lines.iter().rev().take_while(|line| is_footnote_line(line, page.height)).count();
+        // small print without a marker, e.g. a license notice, is not a footnote
+        let start = block.lines[block.lines.len() - small_lines..]
+            .iter()
+            .position(|line| line.starts_with_footnote_marker())
+            .map(|offset| block.lines.len() - small_lines + offset);
+        let start = match start {
+            Some(start) if start > 0 => start,
+            _ => {
+                blocks.push(block);
+                continue;
+            }
+        };
+        let lines = block.lines.split_off(start);
+        let bottom = lines.iter().map(|line| line.y + line.height).fold(0.0, f32::max);
+        let mut footnote = Block::new(block.x, lines[0].y, block.width, bottom - lines[0].y);
+        footnote.lines = lines;
+        footnote.section = block.section.clone();
+        block.height = footnote.y - block.y;
+        blocks.push(block);
+        blocks.push(footnote);
+    }
+    page.blocks = blocks;
+
+    for block in page.blocks.iter_mut() {
+        let text = block.get_text();
+        let title = normalize_heading(&text);
+        if section_titles.contains(&title) {
+            block.block_type = BlockType::Header;
+        } else if config.caption_patterns.parse(&text).is_some() {
+            block.block_type = BlockType::Caption;
+        } else if !block.lines.is_empty()
+            && block.lines[0].starts_with_footnote_marker()
+            && block.lines.iter().all(|line| is_footnote_line(line, page.height))
+        {
+            block.block_type = BlockType::Footnote;
+        } else if block.looks_like_heading(body_font_size) {
+            block.block_type = BlockType::Header;
+        } else {
+            block.block_type = BlockType::Body;
+        }
+    }
+}
+
+/// Pairs each caption with the nearest figure or table region on its page.
+///
+/// The label of a caption decides the candidates: figure captions are paired with
+/// `Page::figure_regions` and table captions with `Page::tables`, both recognized with
+/// `config.caption_patterns`. Among the regions that
+/// overlap the caption horizontally and are not paired yet, the one with the smallest
+/// vertical gap above or below the caption is chosen. The pairs are stored in
+/// `Page::captioned_figures`.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the caption patterns.
+/// * `pages` - A mutable reference to a vector of `Page` instances with classified blocks.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the captions were paired.
+fn parse_associate_captions(config: &ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
+    for page in pages.iter_mut() {
+        let figures = page.figure_regions_with_patterns(&config.caption_patterns);
+        let tables =
+            page.tables.iter().map(|table| table.coord.clone()).collect::<Vec<Coordinate>>();
+        let mut paired: Vec<Coordinate> = Vec::new();
+        let mut captioned_figures = Vec::new();
+        for block in page.blocks.iter().filter(|block| block.block_type == BlockType::Caption) {
+            let text = block.get_text();
+            let (kind, label, _) = match config.caption_patterns.parse(&text) {
+                Some(caption) => caption,
+                None => continue,
+            };
+            let candidates = match kind {
+                CaptionKind::Figure => &figures,
+                CaptionKind::Table => &tables,
+            };
+            let gap = |region: &Coordinate| {
+                if region.bottom_right.y <= block.y + block.height / 2.0 {
+                    block.y - region.bottom_right.y
+                } else {
+                    region.top_left.y - (block.y + block.height)
+                }
+            };
+            let nearest = candidates
+                .iter()
+                .filter(|region| !paired.contains(region))
+                .filter(|region| {
+                    f32::min(block.x + block.width, region.bottom_right.x)
+                        > f32::max(block.x, region.top_left.x)
+                })
+                .min_by(|a, b| gap(a).max(0.0).partial_cmp(&gap(b).max(0.0)).unwrap());
+            if let Some(region) = nearest {
+                paired.push(region.clone());
+                captioned_figures.push(CaptionedFigure {
+                    caption: text,
+                    kind: kind,
+                    label: label,
+                    region: region.clone(),
+                    page: page.page_nubmer,
+                });
+            }
+        }
+        page.captioned_figures = captioned_figures;
+    }
+    return Ok(());
+}
+
+/// Removes footer lines matching `config.footer_patterns` from the bottom of each page.
+///
+/// Only lines in the bottom 15% of a page are considered, and blocks left without lines
+/// are removed.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the footer patterns.
+/// * `pages` - A mutable reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the footers were removed, or an `Err` if a pattern is invalid.
+fn parse_remove_footers(config: &ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
+    let footer_regexes = config
+        .footer_patterns
+        .iter()
+        .map(|pattern| regex::Regex::new(pattern))
+        .collect::<Result<Vec<regex::Regex>, _>>()?;
+    for page in pages.iter_mut() {
+        let footer_top = page.height * 0.85;
+        for block in page.blocks.iter_mut() {
+            block.lines.retain(|line| {
+                let text = line.get_text();
+                line.y < footer_top
+                    || !footer_regexes.iter().any(|regex| regex.is_match(text.trim()))
+            });
+        }
+        page.blocks.retain(|block| !block.lines.is_empty());
+    }
+    return Ok(());
+}
+
+/// Attaches the base64-encoded page images in `config.pdf_figures` to the pages.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the page images.
+/// * `pages` - A mutable reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if every page image was read, or an `Err` if an image could not be read.
+fn parse_embed_page_images(config: &ParserConfig, pages: &mut Vec<Page>) -> Result<()> {
+    for page in pages.iter_mut() {
+        if let Some(fig_path) = config.pdf_figures.get(&page.page_nubmer) {
+            let bytes = std::fs::read(fig_path)?;
+            page.image_data = Some(base64::engine::general_purpose::STANDARD.encode(bytes));
+        }
+    }
+    return Ok(());
+}
+
+/// Drops the detected sections that no block was assigned to.
+///
+/// A title detected by font in `config.sections` that never matches a line in the body
+/// would otherwise produce a section with a title but no content. Each dropped section is
+/// reported as a `ParseDiagnostic::PhantomSection`.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the detected sections.
+/// * `pages` - A reference to a vector of `Page` instances whose blocks have sections assigned.
+fn parse_validate_sections(config: &mut ParserConfig, pages: &Vec<Page>) -> Result<()> {
+    let assigned = pages
+        .iter()
+        .flat_map(|page| page.blocks.iter())
+        .map(|block| normalize_heading(&block.section))
+        .collect::<HashSet<String>>();
+    let mut phantoms = Vec::new();
+    config.sections.retain(|(page, title)| {
+        if assigned.contains(&normalize_heading(title)) {
+            return true;
+        }
+        phantoms.push(ParseDiagnostic::PhantomSection {
+            title: title.clone(),
+            page: *page,
+        });
+        return false;
+    });
+    config.diagnostics.append(&mut phantoms);
+    return Ok(());
+}
+
+/// Collects diagnostics about suspicious parsing results into `config.diagnostics`.
+///
+/// A section is reported as suspicious when the document has more than 4 pages and the
+/// section spans more than half of them.
+fn parse_collect_diagnostics(config: &mut ParserConfig, pages: &Vec<Page>) -> Result<()> {
+    let mut section_pages: Vec<(String, PageNumber, PageNumber)> = Vec::new();
+    for page in pages.iter() {
+        for block in page.blocks.iter() {
+            match section_pages.iter_mut().find(|(title, _, _)| title == &block.section) {
+                Some((_, _, last)) => *last = page.page_nubmer,
+                None => {
+                    section_pages.push((block.section.clone(), page.page_nubmer, page.page_nubmer))
+                }
+            }
+        }
+    }
+    if pages.len() > 4 {
+        for (title, first, last) in section_pages {
+            let span = (last - first) as usize + 1;
+            if span * 2 > pages.len() {
+                config.diagnostics.push(ParseDiagnostic::LongSection {
+                    title: title,
+                    pages: span,
+                });
+            }
+        }
+    }
+
+    if !config.sections.iter().any(|(_, section)| Section::normalize_title(section) == "references")
+    {
+        config.diagnostics.push(ParseDiagnostic::MissingReferences);
+    }
+
+    for page in pages.iter() {
+        if page.number_of_columns < 2 || page.blocks.is_empty() {
+            continue;
+        }
+        let column_width = page.width / page.number_of_columns as f32;
+        let crossing = page
+            .blocks
+            .iter()
+            .filter(|block| {
+                (1..page.number_of_columns).any(|i| {
+                    let boundary = column_width * i as f32;
+                    block.x < boundary && boundary < block.x + block.width
+                })
+            })
+            .count();
+        if crossing * 2 > page.blocks.len() {
+            config.diagnostics.push(ParseDiagnostic::LowColumnConfidence {
+                page: page.page_nubmer,
+            });
+        }
+    }
+    return Ok(());
+}
+
+/// Parses a PDF document from a given URL or local path and extracts its pages.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+///
+/// # Returns
+///
+/// An `async` `Result` containing a vector of `Page` instances if the parsing was successful,
+/// or a `ParseError` describing the cause if an error occurred.
 pub async fn parse(
     path_or_url: &str,
     config: &mut ParserConfig,
     verbose: bool,
-) -> Result<Vec<Page>> {
+) -> Result<Vec<Page>, ParseError> {
     let time = std::time::Instant::now();
     if verbose {
         println!("Parsing PDF...");
     }
+    if let Err(missing) = ParserConfig::check_dependencies() {
+        return Err(ParseError::MissingCommand(missing.join(", ")));
+    }
+    config.diagnostics.clear();
+    config.language = None;
 
     let html = pdf2html(path_or_url, config, verbose, time).await?;
     if verbose {
@@ -798,8 +2276,77 @@ pub async fn parse(
         );
     }
 
+    return parse_pages(config, html, verbose, time);
+}
+
+/// Parses a PDF document from bytes in memory and extracts its pages.
+///
+/// The bytes are written to `config.pdf_path` because the poppler commands need a file;
+/// the file is removed by `ParserConfig::clean_files` like a downloaded PDF.
+///
+/// # Arguments
+///
+/// * `data` - The content of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `verbose` - Whether to print the progress of the parsing.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `Page` instances, or a `ParseError` if an error occurred.
+pub fn parse_from_bytes(
+    data: &[u8],
+    config: &mut ParserConfig,
+    verbose: bool,
+) -> Result<Vec<Page>, ParseError> {
+    let time = std::time::Instant::now();
+    if verbose {
+        println!("Parsing PDF...");
+    }
+    if let Err(missing) = ParserConfig::check_dependencies() {
+        return Err(ParseError::MissingCommand(missing.join(", ")));
+    }
+    config.diagnostics.clear();
+    config.language = None;
+
+    std::fs::write(&config.pdf_path, data)?;
+    convert_pdf(config, verbose, time)?;
+    let html = read_html(config)?;
+    if verbose {
+        println!(
+            "Converted PDF into HTML in {:.2}s",
+            time.elapsed().as_secs()
+        );
+    }
+
+    return parse_pages(config, html, verbose, time);
+}
+
+/// Runs the parsing pipeline on the HTML generated by `pdftotext`.
+///
+/// # Arguments
+///
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `html` - The HTML document generated by `pdftotext`.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `Page` instances, or a `ParseError` if an error occurred.
+fn parse_pages(
+    config: &mut ParserConfig,
+    html: html::Html,
+    verbose: bool,
+    time: std::time::Instant,
+) -> Result<Vec<Page>, ParseError> {
+    match extract_arxiv_id(&html) {
+        Some(arxiv_id) => config.pdf_info.insert("arxiv_id".to_string(), arxiv_id),
+        None => config.pdf_info.remove("arxiv_id"),
+    };
+
     // parse html into pages
     let mut pages = parse_html2pages(config, html)?;
+    if pages.is_empty() {
+        return Err(ParseError::EmptyDocument);
+    }
     if verbose {
         println!(
             "Parsed HTML into Pages in {:.2}s, found {} pages",
@@ -814,18 +2361,70 @@ pub async fn parse(
         println!("Extracted Text Area in {:.2}s", time.elapsed().as_secs(),);
     }
 
+    // remove running headers and footers
+    strip_running_headers(&mut pages);
+
     // adjust columns
     adjst_columns(&mut pages, config);
     if verbose {
         println!("Adjusted Columns in {:.2}s", time.elapsed().as_secs(),);
     }
 
+    // remove conference and preprint footers
+    if config.remove_footers {
+        parse_remove_footers(config, &mut pages)?;
+        if verbose {
+            println!("Removed Footers in {:.2}s", time.elapsed().as_secs(),);
+        }
+    }
+
     // set section for each block
     parse_extract_secsions(config, &mut pages)?;
     if verbose {
         println!("Extracted Sections in {:.2}s", time.elapsed().as_secs(),);
     }
 
+    // set block type for each block
+    parse_classify_blocks(config, &mut pages)?;
+    if verbose {
+        println!("Classified Blocks in {:.2}s", time.elapsed().as_secs(),);
+    }
+
+    // pair captions with figure and table regions
+    parse_associate_captions(config, &mut pages)?;
+
+    // drop sections that were detected but never assigned
+    parse_validate_sections(config, &pages)?;
+
+    // detect the language of the document
+    config.language = Some(analyze_page_language(&pages));
+
+    // collect diagnostics
+    parse_collect_diagnostics(config, &pages)?;
+    if verbose {
+        for diagnostic in config.diagnostics.iter() {
+            println!("Warning: {}", diagnostic);
+        }
+    }
+
+    // embed page images
+    if config.embed_page_images {
+        parse_embed_page_images(config, &mut pages)?;
+        if verbose {
+            println!("Embedded Page Images in {:.2}s", time.elapsed().as_secs(),);
+        }
+    }
+
+    if config.coordinates_in_points {
+        for page in pages.iter_mut() {
+            page.to_pdf_points(config.render_zoom());
+        }
+    }
+
+    if !config.keep_intermediate_files {
+        config.clean_files()?;
+    }
+
     if verbose {
         println!("Finished Parsing in {:.2}s", time.elapsed().as_secs());
     }
@@ -833,24 +2432,1476 @@ pub async fn parse(
     return Ok(pages);
 }
 
-/// Converts a vector of `Page` instances to a JSON string representing the sections of the PDF document.
+/// Parses a PDF document synchronously, for callers without an async runtime.
+///
+/// A current-thread tokio runtime is created internally to run `parse`.
 ///
 /// # Arguments
 ///
-/// * `pages` - A reference to a vector of `Page` instances.
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `verbose` - Whether to print the progress of the parsing.
 ///
 /// # Returns
 ///
-/// A `String` containing the JSON representation of the sections.
-pub fn pages2json(pages: &Vec<Page>) -> String {
-    let sections = Section::from_pages(pages);
-    let mut json_data = Vec::<HashMap<&str, String>>::new();
-    for section in sections.iter() {
-        let mut data = HashMap::new();
-        data.insert("title", section.title.clone());
-        data.insert("contents", section.get_text());
-        json_data.push(data);
+/// A `Result` containing a vector of `Page` instances, or a `ParseError` if an error occurred.
+/// Calling this function from within an async runtime returns `ParseError::InsideRuntime` instead of
+/// panicking; use `parse` there.
+pub fn parse_blocking(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+    verbose: bool,
+) -> Result<Vec<Page>, ParseError> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(ParseError::InsideRuntime);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    return runtime.block_on(parse(path_or_url, config, verbose));
+}
+
+/// Parses a PDF document into a `Paper` with its metadata, sections, references, and tables.
+///
+/// This is the simplest entry point of the library. The lower-level functions such as
+/// `parse` and `Section::from_pages_with_config` remain available for custom pipelines.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `verbose` - Whether to print the progress of the parsing.
+///
+/// # Returns
+///
+/// An `async` `Result` containing the parsed `Paper`, or an `Err` if an error occurred.
+pub async fn parse_paper(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+    verbose: bool,
+) -> Result<Paper> {
+    let pages = parse(path_or_url, config, verbose).await?;
+    return Ok(Paper::from_pages(pages, config));
+}
+
+/// Parses a PDF document and also returns the XML generated by `pdftohtml`.
+///
+/// The XML file itself stays at `config.pdf_xml_path` until `ParserConfig::clean_files` is called,
+/// unless `config.keep_intermediate_files` is `false`.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `verbose` - Whether to print progress messages.
+///
+/// # Returns
+///
+/// An `async` `Result` containing the parsed pages and the XML content.
+pub async fn parse_with_xml(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+    verbose: bool,
+) -> Result<(Vec<Page>, String)> {
+    // the XML is read after parsing, so it is kept until then
+    let keep_intermediate_files = config.keep_intermediate_files;
+    config.keep_intermediate_files = true;
+    let pages = parse(path_or_url, config, verbose).await;
+    config.keep_intermediate_files = keep_intermediate_files;
+    let pages = pages?;
+    let xml = std::fs::read_to_string(&config.pdf_xml_path)?;
+    if !config.keep_intermediate_files {
+        config.clean_files()?;
     }
-    let json = serde_json::to_string(&json_data).unwrap();
-    return json;
+    return Ok((pages, xml));
+}
+
+/// Parses a PDF document into pages without the destructive filtering stages.
+///
+/// The pages are returned right after they are built from the `pdftotext` output, with
+/// tables detected and lines inside tables removed, but before text-area pruning, column
+/// reordering, and section assignment.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+///
+/// # Returns
+///
+/// An `async` `Result` containing a vector of `Page` instances if the parsing was successful, or an `Err` if an error occurred.
+pub async fn parse_raw(path_or_url: &str, config: &mut ParserConfig) -> Result<Vec<Page>> {
+    let time = std::time::Instant::now();
+    config.diagnostics.clear();
+    config.language = None;
+
+    let html = pdf2html(path_or_url, config, false, time).await?;
+    return parse_html2pages(config, html);
+}
+
+/// Parses a PDF document into a stream of pages that are built one at a time while they are consumed.
+///
+/// The `pdftotext` output is read incrementally instead of being loaded into a DOM, so only
+/// the page being built is held in memory. Before the first page is read, one pass over the
+/// output collects what the per-page stages need from the whole document: the section titles
+/// from the XML, the tables of every page with the regions repeated across pages removed (see
+/// `remove_repeated_regions`), and the body font size. Each page then goes through the stages
+/// of `parse` that need only that page: the removal of lines inside tables, footer removal
+/// (`remove_footers`), section assignment, block classification, caption association, page
+/// image embedding (`embed_page_images`), and the conversion to PDF points
+/// (`coordinates_in_points`).
+///
+/// The stages that rearrange blocks across the document are not applied: margin line number
+/// removal, text area pruning, running header removal, and column reordering, as well as
+/// section validation and language detection. A streamed page therefore keeps blocks that
+/// `parse` drops, and a block may get another section when `parse` reorders the columns
+/// around a heading.
+///
+/// Each page is built on a blocking thread with `tokio::task::spawn_blocking`, so driving the
+/// stream does not block the async runtime. The intermediate files are removed once the
+/// stream is exhausted unless `keep_intermediate_files` is set; call
+/// `ParserConfig::clean_files` when dropping it early.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration
+///   for the parsing; pages on which table detection is skipped are reported in `config.diagnostics`.
+///
+/// # Returns
+///
+/// An `async` `Result` containing a `PageStream` that yields each non-empty page in document
+/// order, or a `ParseError` if the PDF document could not be converted.
+pub async fn parse_pages_stream(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+) -> Result<PageStream, ParseError> {
+    let time = std::time::Instant::now();
+    if let Err(missing) = ParserConfig::check_dependencies() {
+        return Err(ParseError::MissingCommand(missing.join(", ")));
+    }
+    config.diagnostics.clear();
+    config.language = None;
+
+    save_pdf(path_or_url, config, false, time).await?;
+    let reader_config = config.clone();
+    let reader = tokio::task::spawn_blocking(move || PageReader::open(reader_config, 1))
+        .await
+        .map_err(|e| ParseError::Other(e.into()))??;
+    config.diagnostics = reader.config.diagnostics.clone();
+    return Ok(PageStream {
+        diagnostics: reader.config.diagnostics.clone(),
+        reader: Some(reader),
+        pending: None,
+    });
+}
+
+/// The attributes of an element in the HTML generated by `pdftotext`, with lowercased names as in `scraper`.
+struct XmlAttributes(HashMap<String, String>);
+
+impl XmlAttributes {
+    fn from_element(e: &quick_xml::events::BytesStart) -> XmlAttributes {
+        return XmlAttributes(
+            e.attributes()
+                .flatten()
+                .map(|attr| {
+                    (
+                        String::from_utf8_lossy(attr.key.as_ref()).to_lowercase(),
+                        String::from_utf8_lossy(attr.value.as_ref()).to_string(),
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    fn number(&self, key: &str) -> f32 {
+        return self.0.get(key).and_then(|value| value.parse::<f32>().ok()).unwrap_or(0.0);
+    }
+
+    /// Returns the box of a block, line, or word as `(x, y, width, height)`.
+    fn coord(&self) -> (f32, f32, f32, f32) {
+        let (xmin, ymin) = (self.number("xmin"), self.number("ymin"));
+        return (
+            xmin,
+            ymin,
+            self.number("xmax") - xmin,
+            self.number("ymax") - ymin,
+        );
+    }
+}
+
+/// Reads the page sizes and the word heights from the HTML generated by `pdftotext`.
+///
+/// # Arguments
+///
+/// * `config` - A reference to a `ParserConfig` instance containing the path to the HTML file.
+///
+/// # Returns
+///
+/// A `Result` containing the number, width, and height of each page in render pixels, and
+/// the font size of every word, as `parse_html2pages` reads them.
+fn read_page_stats(config: &ParserConfig) -> Result<(Vec<(PageNumber, f32, f32)>, Vec<f32>)> {
+    let mut reader = quick_xml::Reader::from_file(&config.pdf_text_path)?;
+    reader.config_mut().check_end_names = false;
+    let zoom = config.render_zoom();
+    let mut page_sizes = Vec::new();
+    let mut font_sizes = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"page" => {
+                    let attrs = XmlAttributes::from_element(&e);
+                    let page_number = page_sizes.len() as PageNumber + 1;
+                    page_sizes.push((
+                        page_number,
+                        attrs.number("width") * zoom,
+                        attrs.number("height") * zoom,
+                    ));
+                }
+                b"word" => {
+                    let (_, _, _, height) = XmlAttributes::from_element(&e).coord();
+                    font_sizes.push(height.max(MIN_WORD_SIZE));
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    return Ok((page_sizes, font_sizes));
+}
+
+/// A stream of the pages of a PDF document, built from the `pdftotext` output as it is read.
+///
+/// Created by `parse_pages_stream`. Pages without any text are skipped, and an `Err` is
+/// yielded once if the HTML cannot be read.
+pub struct PageStream {
+    reader: Option<PageReader>,
+    pending: Option<tokio::task::JoinHandle<(PageReader, Option<Result<Page, ParseError>>)>>,
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl PageStream {
+    /// Returns the diagnostics of the table detection, e.g. the pages on which it was skipped.
+    pub fn diagnostics(&self) -> &Vec<ParseDiagnostic> {
+        return &self.diagnostics;
+    }
+}
+
+impl futures::Stream for PageStream {
+    type Item = Result<Page, ParseError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        if self.pending.is_none() {
+            let mut reader = match self.reader.take() {
+                Some(reader) => reader,
+                None => return std::task::Poll::Ready(None),
+            };
+            self.pending = Some(tokio::task::spawn_blocking(move || {
+                let page = reader.next();
+                (reader, page)
+            }));
+        }
+        let pending = self.pending.as_mut().unwrap();
+        let result = std::task::ready!(std::future::Future::poll(std::pin::Pin::new(pending), cx));
+        self.pending = None;
+        return std::task::Poll::Ready(match result {
+            Ok((reader, page)) => {
+                if page.is_some() {
+                    self.reader = Some(reader);
+                }
+                page
+            }
+            Err(e) => Some(Err(ParseError::Other(e.into()))),
+        });
+    }
+}
+
+/// A blocking iterator over the pages of a PDF document, driven by `PageStream`.
+struct PageReader {
+    config: ParserConfig,
+    reader: quick_xml::Reader<std::io::BufReader<File>>,
+    buf: Vec<u8>,
+    page_number: PageNumber,
+    tables: HashMap<PageNumber, Vec<DetectedTable>>,
+    body_font_size: f32,
+    current_section: String,
+    found_inline_abstract: bool,
+    finished: bool,
+}
+
+impl PageReader {
+    /// Opens the HTML generated by `pdftotext` at `config.pdf_text_path` and detects the tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A `ParserConfig` instance containing the path to the HTML file, the page
+    ///   images, and the detected section titles.
+    /// * `table_start_page` - The first page on which tables are detected.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `PageReader`.
+    fn open(
+        mut config: ParserConfig,
+        table_start_page: PageNumber,
+    ) -> Result<PageReader, ParseError> {
+        let (page_sizes, font_sizes) = read_page_stats(&config)?;
+        let mut tables = detect_tables(&mut config, &page_sizes, table_start_page);
+        remove_repeated_regions(&mut tables);
+        let tables = page_sizes
+            .iter()
+            .map(|(page_number, _, _)| *page_number)
+            .zip(tables)
+            .collect::<HashMap<PageNumber, Vec<DetectedTable>>>();
+
+        let mut reader =
+            quick_xml::Reader::from_file(&config.pdf_text_path).map_err(Error::from)?;
+        reader.config_mut().check_end_names = false;
+        return Ok(PageReader {
+            config: config,
+            reader: reader,
+            buf: Vec::new(),
+            page_number: 0,
+            tables: tables,
+            body_font_size: median_font_size(&font_sizes),
+            current_section: "Abstract".to_string(),
+            found_inline_abstract: false,
+            finished: false,
+        });
+    }
+
+    /// Reads events up to the end of the next page that contains any text.
+    fn read_page(&mut self) -> Result<Option<Page>> {
+        let mut page: Option<Page> = None;
+        let mut block: Option<Block> = None;
+        let mut line: Option<Line> = None;
+        let mut word: Option<(XmlAttributes, String)> = None;
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(e) => {
+                    let attrs = XmlAttributes::from_element(&e);
+                    match e.name().as_ref() {
+                        b"page" => {
+                            self.page_number += 1;
+                            // the page size is in points, the boxes in render pixels
+                            let zoom = self.config.render_zoom();
+                            let (width, height) =
+                                (attrs.number("width") * zoom, attrs.number("height") * zoom);
+                            page = Some(Page::new(width, height, self.page_number));
+                        }
+                        b"block" => {
+                            let (x, y, width, height) = attrs.coord();
+                            block = Some(Block::new(x, y, width, height));
+                        }
+                        b"line" => {
+                            let (x, y, width, height) = attrs.coord();
+                            line = Some(Line::new(x, y, width, height));
+                        }
+                        b"word" => word = Some((attrs, String::new())),
+                        _ => {}
+                    }
+                }
+                Event::Text(e) => {
+                    if let Some((_, text)) = word.as_mut() {
+                        match e.unescape() {
+                            Ok(unescaped) => text.push_str(&unescaped),
+                            Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+                        }
+                    }
+                }
+                Event::End(e) => match e.name().as_ref() {
+                    b"word" => {
+                        if let (Some((attrs, text)), Some(_line)) = (word.take(), line.as_mut()) {
+                            let (x, y, width, height) = attrs.coord();
+                            // pdftotext occasionally emits words with xmax == xmin
+                            _line.add_word(
+                                text,
+                                x,
+                                y,
+                                width.max(MIN_WORD_SIZE),
+                                height.max(MIN_WORD_SIZE),
+                            );
+                        }
+                    }
+                    b"line" => {
+                        if let (Some(mut _line), Some(_block)) = (line.take(), block.as_mut()) {
+                            if self.config.fix_char_spacing {
+                                _line.fix_char_spacing();
+                            }
+                            _block.lines.push(_line);
+                        }
+                    }
+                    b"block" => {
+                        if let (Some(_block), Some(_page)) = (block.take(), page.as_mut()) {
+                            _page.blocks.push(_block);
+                        }
+                    }
+                    b"page" => {
+                        if let Some(mut _page) = page.take() {
+                            _page.tables =
+                                self.tables.remove(&_page.page_nubmer).unwrap_or_default();
+                            dedupe_regions(&mut _page);
+                            collect_table_lines(
+                                &mut _page,
+                                self.config.table_containment_threshold,
+                            );
+                            if _page.blocks.len() > 0 {
+                                return Ok(Some(_page));
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Applies the stages of `parse` that need only the page at hand, see `parse_pages_stream`.
+    fn finish_page(&mut self, page: Page) -> Result<Page> {
+        let mut pages = vec![page];
+        if self.config.remove_footers {
+            parse_remove_footers(&self.config, &mut pages)?;
+        }
+        for page in pages.iter_mut() {
+            assign_page_sections(
+                &self.config,
+                page,
+                &mut self.current_section,
+                &mut self.found_inline_abstract,
+            );
+            classify_page_blocks(&self.config, page, self.body_font_size);
+        }
+        parse_associate_captions(&self.config, &mut pages)?;
+        if self.config.embed_page_images {
+            parse_embed_page_images(&self.config, &mut pages)?;
+        }
+        let mut page = pages.pop().unwrap();
+        if self.config.coordinates_in_points {
+            page.to_pdf_points(self.config.render_zoom());
+        }
+        return Ok(page);
+    }
+}
+
+impl Iterator for PageReader {
+    type Item = Result<Page, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let page = match self.read_page() {
+            Ok(Some(page)) => self.finish_page(page).map(Some),
+            Ok(None) if !self.config.keep_intermediate_files => {
+                self.config.clean_files().map(|_| None)
+            }
+            result => result,
+        };
+        if !matches!(page, Ok(Some(_))) {
+            self.finished = true;
+        }
+        return page.map_err(ParseError::from).transpose();
+    }
+}
+
+/// Parses a PDF document and returns only the sections whose titles match any of `titles`.
+///
+/// Titles are compared after normalization with `Section::normalize_title`, which ignores
+/// case and section numbers and maps known synonyms to a single form, so "Conclusion"
+/// also matches "Conclusions" and "Concluding Remarks", and "Limitations" matches "Limitation".
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+/// * `titles` - The section titles to extract.
+///
+/// # Returns
+///
+/// An `async` `Result` containing the matched sections in document order.
+pub async fn extract_sections(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+    titles: &[&str],
+) -> Result<Vec<Section>> {
+    let pages = parse(path_or_url, config, false).await?;
+    let sections = Section::from_pages_with_config(&pages, config);
+    return Ok(sections
+        .into_iter()
+        .filter(|section| titles.iter().any(|title| section.matches_title(title)))
+        .collect());
+}
+
+/// Extracts the text of the References section of a PDF document.
+///
+/// The pipeline runs only as far as needed to isolate the References section:
+/// table detection is skipped on the pages before the References heading.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+///
+/// # Returns
+///
+/// An `async` `Result` containing the text of the References section, or an `Err` if no References heading was detected.
+pub async fn extract_references_text(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+) -> Result<String> {
+    let time = std::time::Instant::now();
+    let html = pdf2html(path_or_url, config, false, time).await?;
+
+    let references_page = config
+        .sections
+        .iter()
+        .find(|(_, section)| section.to_lowercase() == "references")
+        .map(|(page_number, _)| *page_number);
+    let references_page = match references_page {
+        Some(page_number) => page_number,
+        None => return Err(Error::msg("Error: References section not found")),
+    };
+
+    let mut pages = parse_html2pages_with_tables_from(config, html, references_page)?;
+    parse_extract_textarea(config, &mut pages)?;
+    adjst_columns(&mut pages, config);
+    parse_extract_secsions(config, &mut pages)?;
+
+    let sections = Section::from_pages(&pages);
+    return match sections.iter().find(|section| section.title.to_lowercase() == "references") {
+        Some(section) => Ok(section.get_text()),
+        None => Err(Error::msg("Error: References section not found")),
+    };
+}
+
+/// Extracts the section titles of a PDF document without parsing its pages.
+///
+/// Only the XML conversion and the font-based heading detection of the full pipeline are run;
+/// no page images are rendered and no tables, blocks, or lines are extracted, which makes this
+/// much faster than `parse` when only an index of the headings is needed.
+///
+/// # Arguments
+///
+/// * `path_or_url` - A string slice that holds the URL or local path of the PDF document.
+/// * `config` - A mutable reference to a `ParserConfig` instance containing the configuration for the parsing.
+///
+/// # Returns
+///
+/// An `async` `Result` containing `(page, title, level)` tuples in document order, where the
+/// level is the depth of the section number, e.g. 2 for "3.1", and 1 for unnumbered headings,
+/// or a `ParseError` if an error occurred.
+pub async fn extract_outline(
+    path_or_url: &str,
+    config: &mut ParserConfig,
+) -> Result<Vec<(PageNumber, String, u8)>, ParseError> {
+    let time = std::time::Instant::now();
+    if let Err(missing) = ParserConfig::check_dependencies() {
+        return Err(ParseError::MissingCommand(missing.join(", ")));
+    }
+    config.sections.clear();
+
+    download_pdf(path_or_url, config).await?;
+    save_pdf_as_xml(config, false, time)?;
+    let xml_text = std::fs::read_to_string(&config.pdf_xml_path)?;
+    let outline = outline_levels(&xml_text, &config.sections);
+
+    if !config.keep_intermediate_files {
+        config.clean_files()?;
+    }
+    return Ok(outline);
+}
+
+/// Assigns a level to each detected section title from the section number printed with it.
+///
+/// `pdftohtml` emits the number either in the same text element as the title ("3.1 Encoder")
+/// or in a separate element right before it ("3.1", "Encoder"); both are handled.
+///
+/// # Arguments
+///
+/// * `xml_text` - The XML generated by `pdftohtml`.
+/// * `sections` - The detected section titles, as stored in `ParserConfig::sections`.
+///
+/// # Returns
+///
+/// A vector of `(page, title, level)` tuples in the order of `sections`.
+fn outline_levels(
+    xml_text: &str,
+    sections: &[(PageNumber, String)],
+) -> Vec<(PageNumber, String, u8)> {
+    let regex_is_number = regex::Regex::new(r"^\d+(\.\d+)*\.?$").unwrap();
+    let number_regex = regex::Regex::new(r"^(\d+(?:\.\d+)*)\.?(?:\s|$)").unwrap();
+    let mut outline = sections
+        .iter()
+        .map(|(page_number, title)| (*page_number, title.clone(), 1))
+        .collect::<Vec<(PageNumber, String, u8)>>();
+
+    let mut next = 0;
+    let mut page_number = 0;
+    let mut last_number: Option<String> = None;
+    let mut reader = quick_xml::Reader::from_str(xml_text);
+    reader.config_mut().trim_text(true);
+    while next < outline.len() {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if e.name().as_ref() == b"page" {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"number" {
+                            page_number = String::from_utf8_lossy(attr.value.as_ref())
+                                .parse::<PageNumber>()
+                                .unwrap_or(page_number);
+                        }
+                    }
+                }
+            }
+            Ok(Event::Text(e)) => {
+                let text = String::from_utf8_lossy(e.as_ref()).trim().to_string();
+                let number = number_regex.captures(&text).map(|caps| caps[1].to_string());
+                if regex_is_number.is_match(&text) {
+                    last_number = number;
+                    continue;
+                }
+                let title = strip_section_number(&text);
+                let (section_page, section_title, level) = &mut outline[next];
+                if *section_page == page_number && *section_title == title {
+                    if let Some(number) = number.or(last_number.take()) {
+                        *level = number.split('.').count() as u8;
+                    }
+                    next += 1;
+                }
+                last_number = None;
+            }
+            Ok(Event::Eof) => {
+                break;
+            }
+            Err(_e) => {
+                break;
+            }
+            _ => {}
+        }
+    }
+    return outline;
+}
+
+/// Extracts the table of contents printed in a PDF document, if present.
+///
+/// A table of contents entry is a line ending with dot leaders followed by a page number,
+/// e.g. "2.1 Scope ........ 4". The level of each entry is inferred from the indentation
+/// (`Line::x`) of the line relative to the other entries.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A vector of `TocEntry` instances in document order, empty if no table of contents was found.
+pub fn extract_toc(pages: &Vec<Page>) -> Vec<TocEntry> {
+    let toc_regex = regex::Regex::new(r"^(.*?\S)\s*(?:[\.·…]\s*){3,}(\d+)$").unwrap();
+    let mut entries: Vec<(f32, String, usize)> = Vec::new();
+    for page in pages.iter() {
+        for block in page.blocks.iter() {
+            for line in block.lines.iter() {
+                let text = line.get_text();
+                if let Some(caps) = toc_regex.captures(text.trim()) {
+                    let title = strip_section_number(&caps[1]);
+                    // a run of digits too long for a page number is not a page reference
+                    let page_number = match caps[2].parse::<usize>() {
+                        Ok(page_number) => page_number,
+                        Err(_) => continue,
+                    };
+                    if title.is_empty() {
+                        continue;
+                    }
+                    entries.push((line.x, title, page_number));
+                }
+            }
+        }
+    }
+
+    // cluster indentations within 5pt into levels
+    let mut indents: Vec<f32> = Vec::new();
+    let mut xs = entries.iter().map(|(x, _, _)| *x).collect::<Vec<f32>>();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for x in xs {
+        if indents.last().map(|last| x - last > 5.0).unwrap_or(true) {
+            indents.push(x);
+        }
+    }
+
+    return entries
+        .into_iter()
+        .map(|(x, title, page)| {
+            let level = indents.iter().filter(|indent| **indent <= x + 5.0).count();
+            TocEntry {
+                title: title,
+                page: page,
+                level: level.max(1) as u8,
+            }
+        })
+        .collect();
+}
+
+/// Detects the primary language and script of a PDF document.
+///
+/// The script is decided by counting Latin letters against CJK characters (kana, Han, and
+/// Hangul). Among CJK documents, any kana marks Japanese and a Hangul majority marks
+/// Korean; otherwise the document is Chinese. Latin documents are English when common
+/// English function words make up a noticeable share of the words.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document.
+///
+/// # Returns
+///
+/// A `LanguageProfile` instance describing the document.
+pub fn analyze_language(sections: &[Section]) -> LanguageProfile {
+    let texts = sections
+        .iter()
+        .flat_map(|section| section.contents.iter().map(|content| content.as_str()))
+        .collect::<Vec<&str>>();
+    return language_profile(&texts);
+}
+
+/// Detects the primary language and script of a PDF document from the text of its pages.
+///
+/// This is `analyze_language` applied to the lines of the pages, so the sections do not have
+/// to be built first.
+///
+/// # Arguments
+///
+/// * `pages` - The pages of the document.
+///
+/// # Returns
+///
+/// A `LanguageProfile` instance describing the document.
+pub fn analyze_page_language(pages: &[Page]) -> LanguageProfile {
+    let lines = pages
+        .iter()
+        .flat_map(|page| page.blocks.iter())
+        .flat_map(|block| block.lines.iter())
+        .map(|line| line.get_text())
+        .collect::<Vec<String>>();
+    return language_profile(&lines.iter().map(|line| line.as_str()).collect::<Vec<&str>>());
+}
+
+/// Counts the letters and English function words of a text, see `analyze_language`.
+fn language_profile(texts: &[&str]) -> LanguageProfile {
+    let mut latin = 0usize;
+    let mut kana = 0usize;
+    let mut han = 0usize;
+    let mut hangul = 0usize;
+    let mut other = 0usize;
+    for c in texts.iter().flat_map(|text| text.chars()) {
+        match c {
+            'a'..='z' | 'A'..='Z' => latin += 1,
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => hangul += 1,
+            _ if c.is_alphabetic() => other += 1,
+            _ => {}
+        }
+    }
+
+    let cjk = kana + han + hangul;
+    let total = latin + cjk + other;
+    if total == 0 {
+        return LanguageProfile {
+            primary: Lang::Unknown,
+            script: Script::Other,
+            confidence: 0.0,
+        };
+    }
+
+    if cjk > latin && cjk > other {
+        let primary = if kana > 0 {
+            Lang::Japanese
+        } else if hangul * 2 > cjk {
+            Lang::Korean
+        } else {
+            Lang::Chinese
+        };
+        return LanguageProfile {
+            primary: primary,
+            script: Script::Cjk,
+            confidence: cjk as f32 / total as f32,
+        };
+    }
+
+    if latin >= other {
+        let stopwords = ["the", "of", "and", "to", "in", "is", "we", "for", "that"];
+        let words = texts
+            .iter()
+            .flat_map(|text| text.split_whitespace())
+            .map(|word| word.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<String>>();
+        let hits = words.iter().filter(|word| stopwords.contains(&word.as_str())).count();
+        let primary = if !words.is_empty() && hits * 10 >= words.len() {
+            Lang::English
+        } else {
+            Lang::Unknown
+        };
+        return LanguageProfile {
+            primary: primary,
+            script: Script::Latin,
+            confidence: latin as f32 / total as f32,
+        };
+    }
+
+    return LanguageProfile {
+        primary: Lang::Unknown,
+        script: Script::Other,
+        confidence: other as f32 / total as f32,
+    };
+}
+
+/// Extracts the reported numbers of a section, e.g. percentages, scores, and measurements.
+///
+/// Decimal numbers such as "0.05" and numbers followed by a unit such as "92%" or "120 ms"
+/// are extracted. Bare integers are skipped, since they are mostly years, counts, and
+/// references to figures or sections.
+///
+/// # Arguments
+///
+/// * `section` - The section to scan.
+///
+/// # Returns
+///
+/// A vector of `NumericMention` instances in order of appearance, each with up to 40
+/// characters of context on both sides.
+pub fn extract_numbers(section: &Section) -> Vec<NumericMention> {
+    let number_regex = regex::Regex::new(
+        r"(?:^|[^\w.])(-?\d+(?:,\d{3})*(?:\.\d+)?)\s?(%|ms|GB|MB|KB|GHz|Hz|dB|pp|[smhxKMB×])?(?:$|[^\w%])",
+    )
+    .unwrap();
+    let context_chars = 40;
+    let mut mentions = Vec::new();
+    for content in section.contents.iter() {
+        let chars = content.char_indices().map(|(i, _)| i).collect::<Vec<usize>>();
+        let mut start = 0;
+        while let Some(caps) = number_regex.captures_at(content, start) {
+            let number = caps.get(1).unwrap();
+            let unit = caps.get(2).map(|unit| unit.as_str().to_string());
+            start = caps.get(2).unwrap_or(number).end();
+            if !number.as_str().contains('.') && unit.is_none() {
+                continue;
+            }
+            let value = match number.as_str().replace(',', "").parse::<f64>() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+
+            let first = chars.partition_point(|i| *i < number.start());
+            let last = chars.partition_point(|i| *i < start);
+            let context_start = chars[first.saturating_sub(context_chars)];
+            let context_end = chars.get(last + context_chars).copied().unwrap_or(content.len());
+            mentions.push(NumericMention {
+                value: value,
+                unit: unit,
+                context: content[context_start..context_end].trim().to_string(),
+            });
+        }
+    }
+    return mentions;
+}
+
+/// Detects whether a paper uses numeric or author-year citations.
+///
+/// Numeric markers such as "[12]" or "[3, 5-7]" and author-year markers such as
+/// "(Smith, 2023)" or "Smith et al. (2023)" are counted in the body sections, and
+/// references starting with "[n]" are counted in the References section. The style
+/// with more hits wins.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document.
+///
+/// # Returns
+///
+/// A `CitationStyle` value.
+pub fn detect_citation_style(sections: &[Section]) -> CitationStyle {
+    let numeric_regex = regex::Regex::new(r"\[\d+(\s*[,–-]\s*\d+)*\]").unwrap();
+    let author_year_regex = regex::Regex::new(
+        r"[A-Z][A-Za-z'-]+(\s+et\s+al\.|\s+(and|&)\s+[A-Z][A-Za-z'-]+)?,?\s*\(?(19|20)\d{2}[a-z]?\)",
+    )
+    .unwrap();
+    let numbered_reference_regex = regex::Regex::new(r"^\s*\[\d+\]").unwrap();
+
+    let mut numeric = 0;
+    let mut author_year = 0;
+    for section in sections {
+        if section.is_references() {
+            numeric += section
+                .contents
+                .iter()
+                .filter(|content| numbered_reference_regex.is_match(content))
+                .count();
+        } else {
+            for content in section.contents.iter() {
+                numeric += numeric_regex.find_iter(content).count();
+                author_year += author_year_regex.find_iter(content).count();
+            }
+        }
+    }
+
+    if numeric == 0 && author_year == 0 {
+        return CitationStyle::Unknown;
+    } else if numeric >= author_year {
+        return CitationStyle::Numeric;
+    } else {
+        return CitationStyle::AuthorYear;
+    }
+}
+
+/// Splits the sections of a PDF document into front matter, body, and back matter.
+///
+/// The leading abstract sections form the front matter. The back matter starts at the
+/// first acknowledgments, references, or appendix section and includes every section
+/// after it. The remaining sections form the body.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document in document order.
+///
+/// # Returns
+///
+/// A `DocumentParts` instance containing the three parts.
+pub fn partition_document(sections: &[Section]) -> DocumentParts {
+    let mut parts = DocumentParts::default();
+    for section in sections.iter() {
+        if !parts.back.is_empty()
+            || section.is_references()
+            || section.is_appendix()
+            || section.is_acknowledgments()
+        {
+            parts.back.push(section.clone());
+        } else if parts.body.is_empty() && section.is_abstract() {
+            parts.front.push(section.clone());
+        } else {
+            parts.body.push(section.clone());
+        }
+    }
+    return parts;
+}
+
+/// Returns the section with the given title and all sections after it.
+///
+/// Titles are compared with `Section::matches_title`, and sections are returned in
+/// document order, i.e. sorted by `Section::index`.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document.
+/// * `title` - The title of the first section to return, e.g. "Appendix".
+///
+/// # Returns
+///
+/// A vector containing the matched section and the sections after it, or an empty vector if no section matches.
+pub fn sections_after(sections: &[Section], title: &str) -> Vec<Section> {
+    let mut sections = sections.to_vec();
+    sections.sort_by_key(|section| section.index);
+    return match sections.iter().position(|section| section.matches_title(title)) {
+        Some(start) => sections.split_off(start),
+        None => Vec::new(),
+    };
+}
+
+/// Returns the sections from the section titled `start` up to, but not including, the section titled `end`.
+///
+/// Titles are compared with `Section::matches_title`, and sections are returned in
+/// document order. When no section after `start` matches `end`, all sections from
+/// `start` onward are returned.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document.
+/// * `start` - The title of the first section to return.
+/// * `end` - The title of the section at which to stop.
+///
+/// # Returns
+///
+/// A vector containing the sections between the two titles, or an empty vector if no section matches `start`.
+pub fn sections_between(sections: &[Section], start: &str, end: &str) -> Vec<Section> {
+    let mut sections = sections_after(sections, start);
+    if let Some(stop) = sections.iter().skip(1).position(|section| section.matches_title(end)) {
+        sections.truncate(stop + 1);
+    }
+    return sections;
+}
+
+/// Splits the text of a References section into individual references.
+///
+/// The section is split as by `Section::references_raw`, so the entries are the texts from
+/// which `Paper::references` is parsed.
+///
+/// # Arguments
+///
+/// * `section` - The References section.
+///
+/// # Returns
+///
+/// A vector containing the text of each reference.
+pub fn split_references(section: &Section) -> Vec<String> {
+    return Section::references_raw(std::slice::from_ref(section));
+}
+
+/// Splits the text of a reference list into individual entries.
+///
+/// Entries numbered as "[1] ...", "[2] ..." are split at their markers. Otherwise entries in
+/// author-year style are split before a "Surname, I." that follows the end of a sentence,
+/// once the current entry contains a year, e.g. "... 2019. Smith, J. ...".
+///
+/// # Arguments
+///
+/// * `text` - The text of the reference list.
+///
+/// # Returns
+///
+/// A vector containing the text of each entry.
+pub fn split_reference_text(text: &str) -> Vec<String> {
+    let author_regex = regex::Regex::new(r"\.\s+[\p{Lu}][\p{L}'\-]+,\s+(?:\p{Lu}\.\s*)+").unwrap();
+    let year_regex = regex::Regex::new(r"\b(?:19|20)\d{2}[a-z]?\b").unwrap();
+
+    let mut starts =
+        REFERENCE_MARKER_REGEX.find_iter(text).map(|m| m.start()).collect::<Vec<usize>>();
+    if starts.is_empty() {
+        starts.push(0);
+        for m in author_regex.find_iter(text) {
+            let start = m.start() + 1;
+            if year_regex.is_match(&text[*starts.last().unwrap()..start]) {
+                starts.push(start);
+            }
+        }
+    }
+    let mut entries = Vec::new();
+    for (i, start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(text.len());
+        let entry = text[*start..end].trim();
+        if !entry.is_empty() {
+            entries.push(entry.to_string());
+        }
+    }
+    return entries;
+}
+
+/// Parses a reference list into structured references without a language model.
+///
+/// The text is split with `split_reference_text`. The year, DOI, arXiv identifier, and URL
+/// are extracted with regular expressions. The authors are the text up to the first period
+/// that ends a word of two or more letters or a year, e.g. "A. Smith and B. Jones." or
+/// "Smith, J., Jones, B. 2020.", and the title is the following sentence.
+///
+/// # Arguments
+///
+/// * `references_text` - The text of the References section.
+///
+/// # Returns
+///
+/// A vector of `Reference` instances in the order of the list.
+pub fn parse_references_heuristic(references_text: &str) -> Vec<Reference> {
+    let doi_regex = regex::Regex::new(r"(?i)\b(10\.\d+/[^\s,;]+)").unwrap();
+    let arxiv_regex =
+        regex::Regex::new(r"(?i)(?:arxiv:\s*|arxiv\.org/abs/)(\d{4}\.\d{4,5})(?:v\d+)?").unwrap();
+    let url_regex = regex::Regex::new(r"https?://[^\s,;]+").unwrap();
+    let year_regex = regex::Regex::new(r"\b((?:19|20)\d{2})[a-z]?\b").unwrap();
+    let marker_regex = regex::Regex::new(r"^\[\d+\]\s*").unwrap();
+    let sentence_regex = regex::Regex::new(r"(?:\p{L}{2,}|\d{4}[a-z]?\)?)[.?!]\s+").unwrap();
+    let trailing_year_regex = regex::Regex::new(r"[\s,]*\(?\d{4}[a-z]?\)?$").unwrap();
+    let separator_regex = regex::Regex::new(r",\s*and\s+|\s+and\s+|\s*&\s*|,\s*").unwrap();
+    let initials_regex = regex::Regex::new(r"^(?:\p{Lu}\.\s*-?)+$").unwrap();
+    let trim = |text: &str| text.trim_end_matches(|c| c == '.' || c == ')').to_string();
+
+    let mut references = Vec::new();
+    for entry in split_reference_text(references_text) {
+        let doi = doi_regex.captures(&entry).map(|caps| trim(&caps[1]));
+        let arxiv_id = arxiv_regex.captures(&entry).map(|caps| caps[1].to_string());
+        let url = url_regex.find(&entry).map(|m| trim(m.as_str()));
+
+        // identifiers such as "arXiv:2010.12345" must not be read as years
+        let mut plain = entry.clone();
+        for regex in [&doi_regex, &arxiv_regex, &url_regex] {
+            plain = regex.replace_all(&plain, " ").to_string();
+        }
+        let year = year_regex.captures(&plain).and_then(|caps| caps[1].parse::<u16>().ok());
+
+        let body = marker_regex.replace(&plain, "").to_string();
+        let sentence_ends =
+            sentence_regex.find_iter(&body).map(|m| m.end()).collect::<Vec<usize>>();
+        let (authors, title) = match sentence_ends.first() {
+            Some(end) => {
+                let title_end = sentence_ends.get(1).copied().unwrap_or(body.len());
+                let authors = body[..*end].trim().trim_end_matches('.');
+                let authors = trailing_year_regex.replace(authors, "").to_string();
+                let title = body[*end..title_end].trim().trim_end_matches('.').to_string();
+                (authors, if title.is_empty() { None } else { Some(title) })
+            }
+            None => (String::new(), None),
+        };
+
+        // "Smith, J., Jones, B." alternates surnames and initials
+        let mut names: Vec<String> = Vec::new();
+        for part in separator_regex.split(&authors) {
+            let part = part.trim();
+            if part.is_empty() || part == "et al" || part == "et al." {
+                continue;
+            }
+            match names.last_mut() {
+                Some(last) if initials_regex.is_match(part) && !last.contains(' ') => {
+                    last.push_str(", ");
+                    last.push_str(part);
+                }
+                _ => names.push(part.to_string()),
+            }
+        }
+
+        references.push(Reference {
+            text: entry,
+            authors: names,
+            title: title,
+            year: year,
+            doi: doi,
+            arxiv_id: arxiv_id,
+            url: url,
+        });
+    }
+    return references;
+}
+
+/// Finds the in-text citations of a PDF document and links them to its references.
+///
+/// Only the markers of the citation style of the document are matched, see
+/// `detect_citation_style`, so bracketed numbers in an author-year paper are not linked;
+/// both kinds are matched if the style is unknown.
+///
+/// Numeric markers such as "[3]", "[1, 4]", or "[2-5]" cite the reference numbered "[n]", or
+/// the n-th reference if the list is not numbered; a marker citing several references yields
+/// one `Citation` per reference, with `reference_index` set to `None` for a number without a
+/// reference. A bracketed list that contains 0, such as the interval "[0, 1]", is not a
+/// citation. Author-year markers such as "(Vaswani et al., 2017)" and
+/// "Vaswani et al. (2017)" cite the reference of the same year whose author surname is most
+/// similar to the cited surname by trigram similarity. Markers in the References section are
+/// ignored.
+///
+/// # Arguments
+///
+/// * `pages` - The parsed pages of the PDF document.
+/// * `references` - The parsed references, see `parse_references_heuristic`.
+///
+/// # Returns
+///
+/// A vector of `Citation` instances in reading order.
+pub fn find_citations(pages: &[Page], references: &[Reference]) -> Vec<Citation> {
+    let numeric_regex = regex::Regex::new(r"\[(\d+(?:\s*[-–,]\s*\d+)*)\]").unwrap();
+    let group_regex = regex::Regex::new(r"\(([^()]*\b(?:19|20)\d{2}[a-z]?)\)").unwrap();
+    let author_year_regex = regex::Regex::new(
+        r"([\p{Lu}][\p{L}'\-]+)(?:\s+et\s+al\.?|\s+(?:and|&)\s+[\p{Lu}][\p{L}'\-]+)?,?\s+((?:19|20)\d{2})[a-z]?$",
+    )
+    .unwrap();
+    let narrative_regex = regex::Regex::new(
+        r"([\p{Lu}][\p{L}'\-]+)(?:\s+et\s+al\.|\s+(?:and|&)\s+[\p{Lu}][\p{L}'\-]+)?\s+\(((?:19|20)\d{2})[a-z]?\)",
+    )
+    .unwrap();
+
+    let numbered = references
+        .iter()
+        .map(|reference| {
+            let number = reference.text.trim_start().strip_prefix('[')?.split(']').next()?;
+            return number.parse::<usize>().ok();
+        })
+        .collect::<Vec<Option<usize>>>();
+    let numeric_index = |number: usize| {
+        if numbered.iter().any(|n| n.is_some()) {
+            return numbered.iter().position(|n| *n == Some(number));
+        }
+        return if 0 < number && number <= references.len() {
+            Some(number - 1)
+        } else {
+            None
+        };
+    };
+    let author_year_index = |surname: &str, year: u16| {
+        let mut best: Option<(f64, usize)> = None;
+        for (i, reference) in references.iter().enumerate() {
+            if reference.year != Some(year) {
+                continue;
+            }
+            let surnames = if reference.authors.is_empty() {
+                reference.text.split_whitespace().take(3).map(|word| word.to_string()).collect()
+            } else {
+                reference
+                    .authors
+                    .iter()
+                    .map(|author| match author.split_once(',') {
+                        Some((surname, _)) => surname.to_string(),
+                        None => author.split_whitespace().last().unwrap_or("").to_string(),
+                    })
+                    .collect::<Vec<String>>()
+            };
+            let similarity =
+                surnames.iter().map(|name| trigram_similarity(surname, name)).fold(0.0, f64::max);
+            if similarity >= 0.5 && best.map_or(true, |(score, _)| similarity > score) {
+                best = Some((similarity, i));
+            }
+        }
+        return best.map(|(_, i)| i);
+    };
+
+    // mixing the matchers links e.g. "[0, 1]" or "(2017)" in the wrong style
+    let style = detect_citation_style(&Section::from_pages(&pages.to_vec()));
+    let match_numeric = style != CitationStyle::AuthorYear;
+    let match_author_year = style != CitationStyle::Numeric;
+
+    let mut citations = Vec::new();
+    for page in pages {
+        for block in page.blocks.iter() {
+            if Section::normalize_title(&block.section) == "references" {
+                continue;
+            }
+            let text = block.get_text();
+            let mut found: Vec<(usize, Citation)> = Vec::new();
+            let numeric_matches = match match_numeric {
+                true => numeric_regex.captures_iter(&text).collect::<Vec<_>>(),
+                false => Vec::new(),
+            };
+            for caps in numeric_matches {
+                let start = caps.get(0).unwrap().start();
+                let mut numbers = Vec::new();
+                for part in caps[1].split(',') {
+                    let bounds = part
+                        .split(['-', '–'])
+                        .filter_map(|n| n.trim().parse::<usize>().ok())
+                        .collect::<Vec<usize>>();
+                    match bounds[..] {
+                        [first, last] if first <= last && last - first < 100 => {
+                            numbers.extend(first..=last)
+                        }
+                        _ => numbers.extend(bounds),
+                    }
+                }
+                // no reference is numbered 0, so e.g. the interval "[0, 1]" is not a citation
+                if numbers.contains(&0) {
+                    continue;
+                }
+                for reference_index in numbers.iter().map(|n| numeric_index(*n)) {
+                    found.push((
+                        start,
+                        Citation {
+                            marker: caps[0].to_string(),
+                            section: block.section.clone(),
+                            reference_index: reference_index,
+                        },
+                    ));
+                }
+            }
+            if !match_author_year {
+                found.sort_by_key(|(start, _)| *start);
+                citations.extend(found.into_iter().map(|(_, citation)| citation));
+                continue;
+            }
+            for caps in group_regex.captures_iter(&text) {
+                let start = caps.get(0).unwrap().start();
+                for part in caps[1].split(';') {
+                    if let Some(cite) = author_year_regex.captures(part.trim()) {
+                        let year = cite[2].parse::<u16>().unwrap();
+                        found.push((
+                            start,
+                            Citation {
+                                marker: part.trim().to_string(),
+                                section: block.section.clone(),
+                                reference_index: author_year_index(&cite[1], year),
+                            },
+                        ));
+                    }
+                }
+            }
+            for caps in narrative_regex.captures_iter(&text) {
+                let year = caps[2].parse::<u16>().unwrap();
+                found.push((
+                    caps.get(0).unwrap().start(),
+                    Citation {
+                        marker: caps[0].to_string(),
+                        section: block.section.clone(),
+                        reference_index: author_year_index(&caps[1], year),
+                    },
+                ));
+            }
+            found.sort_by_key(|(start, _)| *start);
+            citations.extend(found.into_iter().map(|(_, citation)| citation));
+        }
+    }
+    return citations;
+}
+
+/// Renders the sections of a PDF document as a Markdown document.
+///
+/// # Arguments
+///
+/// * `sections` - The sections of the document in document order.
+///
+/// # Returns
+///
+/// A `String` containing the Markdown of every section, see `Section::to_markdown`.
+pub fn sections_to_markdown(sections: &[Section]) -> String {
+    return sections
+        .iter()
+        .map(|section| section.to_markdown())
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+/// Converts a parsed paper into a minimal GROBID-compatible TEI XML document.
+///
+/// The title and authors go into `<teiHeader>`, the abstract into `<profileDesc>`, every
+/// other section into a `<div>` of `<body>`, and each reference of the References section
+/// into a `<biblStruct>` of `<listBibl>` holding the raw reference text.
+///
+/// # Arguments
+///
+/// * `paper` - The parsed paper.
+///
+/// # Returns
+///
+/// A `String` containing the TEI XML document.
+pub fn sections_to_tei(paper: &Paper) -> String {
+    let escape = |text: &str| quick_xml::escape::escape(text).to_string();
+    let mut tei = String::new();
+    tei.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    tei.push_str("<TEI xmlns=\"http://www.tei-c.org/ns/1.0\">\n");
+
+    // header
+    tei.push_str("  <teiHeader>\n    <fileDesc>\n      <titleStmt>\n");
+    tei.push_str(&format!(
+        "        <title level=\"a\" type=\"main\">{}</title>\n",
+        escape(paper.metadata.title.as_deref().unwrap_or(""))
+    ));
+    tei.push_str(
+        "      </titleStmt>\n      <sourceDesc>\n        <biblStruct>\n          <analytic>\n",
+    );
+    for author in paper.metadata.authors.iter() {
+        tei.push_str(&format!(
+            "            <author><persName>{}</persName></author>\n",
+            escape(author)
+        ));
+    }
+    tei.push_str(
+        "          </analytic>\n        </biblStruct>\n      </sourceDesc>\n    </fileDesc>\n",
+    );
+    tei.push_str("    <profileDesc>\n      <abstract>\n");
+    for section in paper.sections.iter().filter(|section| section.is_abstract()) {
+        for content in section.contents.iter() {
+            tei.push_str(&format!("        <p>{}</p>\n", escape(content)));
+        }
+    }
+    tei.push_str("      </abstract>\n    </profileDesc>\n  </teiHeader>\n");
+
+    // body
+    tei.push_str("  <text>\n    <body>\n");
+    for section in paper.sections.iter() {
+        if section.is_abstract() || section.is_references() {
+            continue;
+        }
+        tei.push_str("      <div>\n");
+        match &section.number {
+            Some(number) => tei.push_str(&format!(
+                "        <head n=\"{}\">{}</head>\n",
+                escape(number),
+                escape(&section.title)
+            )),
+            None => tei.push_str(&format!(
+                "        <head>{}</head>\n",
+                escape(&section.title)
+            )),
+        }
+        for content in section.contents.iter() {
+            tei.push_str(&format!("        <p>{}</p>\n", escape(content)));
+        }
+        tei.push_str("      </div>\n");
+    }
+    tei.push_str("    </body>\n");
+
+    // references
+    tei.push_str("    <back>\n      <div type=\"references\">\n        <listBibl>\n");
+    for reference in paper.references.iter() {
+        tei.push_str(&format!(
+            "          <biblStruct><note type=\"raw_reference\">{}</note></biblStruct>\n",
+            escape(&reference.text)
+        ));
+    }
+    tei.push_str("        </listBibl>\n      </div>\n    </back>\n  </text>\n</TEI>\n");
+    return tei;
+}
+
+/// Writes sections as JSON to a writer without building the whole string in memory.
+///
+/// # Arguments
+///
+/// * `sections` - The sections to write.
+/// * `writer` - The writer, e.g. a file or a socket.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the sections were written, or an `Err` if serialization or writing failed.
+pub fn write_sections_json<W: std::io::Write>(sections: &[Section], writer: W) -> Result<()> {
+    serde_json::to_writer(writer, sections)?;
+    return Ok(());
+}
+
+/// Writes the layout of pages as JSON to a writer without building the whole string in memory.
+///
+/// # Arguments
+///
+/// * `pages` - The pages to write.
+/// * `writer` - The writer, e.g. a file or a socket.
+///
+/// # Returns
+///
+/// A `Result` which is `Ok` if the pages were written, or an `Err` if serialization or writing failed.
+pub fn write_pages_json<W: std::io::Write>(pages: &[Page], writer: W) -> Result<()> {
+    serde_json::to_writer(writer, pages)?;
+    return Ok(());
+}
+
+/// Converts a vector of `Page` instances to a JSON string representing the sections of the PDF document.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `Result` containing the JSON representation of the sections, or the serialization error.
+pub fn try_pages2json(pages: &Vec<Page>) -> Result<String> {
+    let sections = Section::from_pages(pages);
+    let mut json_data = Vec::<HashMap<&str, String>>::new();
+    for section in sections.iter() {
+        let mut data = HashMap::new();
+        data.insert("title", section.title.clone());
+        data.insert("contents", section.get_text());
+        json_data.push(data);
+    }
+    let json = serde_json::to_string(&json_data)?;
+    return Ok(json);
+}
+
+/// Converts a vector of `Page` instances to a JSON string representing the sections of the PDF document.
+///
+/// This is the infallible counterpart of `try_pages2json` and panics if serialization fails.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A `String` containing the JSON representation of the sections.
+pub fn pages2json(pages: &Vec<Page>) -> String {
+    return try_pages2json(pages).expect("failed to serialize sections to JSON");
 }
@@ -1,10 +1,24 @@
+use crate::error::ParseError;
 use anyhow::Result;
+use opencv::core::Rect;
+use opencv::imgcodecs;
+use opencv::prelude::*;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, OnceLock};
 
-pub type PageNumber = i8;
+pub type PageNumber = i32;
+
+/// The poppler commands used to convert PDF documents.
+pub const POPPLER_COMMANDS: [&str; 4] = ["pdfinfo", "pdftocairo", "pdftohtml", "pdftotext"];
+
+/// The minimum height in points of the area above a figure caption that is treated as a figure.
+const MIN_FIGURE_HEIGHT: f32 = 20.0;
+
+/// The embedded English word list used to resolve hyphens at line ends, one lowercase lemma per line.
+const ENGLISH_WORDS: &str = include_str!("words.txt");
 
 /// `ParserConfig` is a configuration structure for parsing PDF documents.
 ///
@@ -13,16 +27,44 @@ pub type PageNumber = i8;
 /// * `pdf_path` - The file path to the PDF document.
 /// * `pdf_text_path` - The file path to the extracted text from the PDF document.
 /// * `pdf_figures` - A map of page numbers to file paths of extracted figures from the PDF document.
-/// * `pdf_xml_path` - The file path to the extracted XML data from the PDF document.
+/// * `pdf_xml_path` - The file path to the extracted XML data from the PDF document; the file is kept until `clean_files` is called.
 /// * `sections` - A vector of tuples containing page numbers and section titles.
 /// * `pdf_info` - A map containing metadata information about the PDF document.
+/// * `include_block_types` - Whether `Section::content_types` and `Section::blocks` are populated when building sections.
+/// * `fix_char_spacing` - Whether lines with one glyph per word are re-joined into words.
+/// * `diagnostics` - Diagnostics collected by `parse` about suspicious results.
+/// * `table_containment_threshold` - The minimum fraction of a line's area inside a detected table for the line to be removed.
+/// * `normalize_punctuation` - Whether smart quotes and dashes in section contents are normalized to ASCII.
+/// * `section_aliases` - A map of section titles to canonical titles applied when building sections, ordered so that the first matching alias wins.
+/// * `drop_sections` - Section titles excluded from the sections built from the pages, compared after normalization.
+/// * `language` - The language profile of the last parsed document, used to route hyphen merging and line joining.
+/// * `coordinates_in_points` - Whether to convert the coordinates of the parsed pages from render pixels into PDF points.
+/// * `embed_page_images` - Whether to attach the base64-encoded page images to the parsed pages.
+/// * `min_block_width_ratio` - Blocks narrower than this fraction of the column width are pruned as narrow blocks, unless they have at least `min_block_lines` lines.
+/// * `min_block_lines` - The number of lines from which a narrow block is kept.
+/// * `url_rewrites` - Pairs of URL prefixes and their replacements applied to the input URL before downloading.
+/// * `use_arxiv_mirror` - Whether arxiv.org URLs are downloaded from export.arxiv.org instead.
+/// * `remove_footers` - Whether conference and preprint footer lines at the bottom of pages are removed.
+/// * `footer_patterns` - Regular expressions matching the footer lines removed with `remove_footers`.
+/// * `section_detector` - A user-provided section detector that replaces the font-based section detection.
+/// * `include_elements` - Whether `Section::elements` is populated with the contents and captions in reading order.
+/// * `dpi` - The resolution in DPI at which `pdftocairo` and `pdftotext` render the pages.
+/// * `keep_intermediate_files` - Whether the downloaded PDF, the HTML and XML files, and the page images are kept after `parse`; they are removed with `clean_files` otherwise.
+/// * `min_table_confidence` - Detected tables with a `DetectedTable::confidence` below this value are discarded.
+/// * `caption_patterns` - The patterns that recognize figure and table captions, e.g. "Figure 1:" or "図 1".
+/// * `section_keywords` - The well-known section titles whose font identifies the section headings, compared ignoring case and section numbers.
+/// * `dictionary_hyphenation` - Whether a hyphen at the end of a line is only removed when the joined word is in the embedded English word list, see `Block::get_text_with_dictionary`.
+/// * `track_provenance` - Whether `Section::spans` is populated with the page and the bounding box of the block behind each entry of `contents`.
 ///
 /// # Methods
 ///
 /// * `new` - Creates a new instance of `ParserConfig` with default values.
 /// * `pdf_width` - Returns the width of the PDF document as an `i32`.
 /// * `pdf_height` - Returns the height of the PDF document as an `i32`.
+/// * `rewrite_url` - Rewrites the input URL before downloading.
 /// * `clean_files` - Removes the PDF, text, XML, and figure files associated with the `ParserConfig`.
+/// * `paper_title` - Returns the title of the paper from the PDF metadata or the first page.
+/// * `arxiv_id` - Returns the arXiv identifier found in the watermark of the parsed document.
 //
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParserConfig {
@@ -32,6 +74,31 @@ pub struct ParserConfig {
     pub pdf_xml_path: String,
     pub sections: Vec<(PageNumber, String)>,
     pub pdf_info: HashMap<String, String>,
+    pub include_block_types: bool,
+    pub fix_char_spacing: bool,
+    pub diagnostics: Vec<ParseDiagnostic>,
+    pub table_containment_threshold: f32,
+    pub normalize_punctuation: bool,
+    pub section_aliases: BTreeMap<String, String>,
+    pub drop_sections: Vec<String>,
+    pub language: Option<LanguageProfile>,
+    pub coordinates_in_points: bool,
+    pub embed_page_images: bool,
+    pub min_block_width_ratio: f32,
+    pub min_block_lines: usize,
+    pub url_rewrites: Vec<(String, String)>,
+    pub use_arxiv_mirror: bool,
+    pub remove_footers: bool,
+    pub footer_patterns: Vec<String>,
+    pub section_detector: Option<SectionDetector>,
+    pub include_elements: bool,
+    pub dpi: u32,
+    pub keep_intermediate_files: bool,
+    pub min_table_confidence: f32,
+    pub caption_patterns: CaptionPatterns,
+    pub section_keywords: Vec<String>,
+    pub dictionary_hyphenation: bool,
+    pub track_provenance: bool,
 }
 
 impl ParserConfig {
@@ -44,30 +111,96 @@ impl ParserConfig {
     /// - `pdf_xml_path`: The path to the raw XML version of the PDF.
     /// - `sections`: An empty vector to store sections of the parsed PDF.
     /// - `pdf_info`: An empty `HashMap` to store additional PDF information.
+    /// - `include_block_types`: `false`, so `Section::content_types` and `Section::blocks` stay empty.
+    /// - `fix_char_spacing`: `false`, so words are kept as emitted by `pdftotext`.
+    /// - `diagnostics`: An empty vector to store diagnostics of the parsing.
+    /// - `table_containment_threshold`: `0.3`.
+    /// - `normalize_punctuation`: `false`, so punctuation is kept as extracted.
+    /// - `section_aliases`: An empty `BTreeMap`, so section titles are kept as detected.
+    /// - `drop_sections`: An empty vector, so no section is dropped.
+    /// - `language`: None
+    /// - `coordinates_in_points`: false
+    /// - `embed_page_images`: false
+    /// - `min_block_width_ratio`: 0.3
+    /// - `min_block_lines`: 4
+    /// - `url_rewrites`: []
+    /// - `use_arxiv_mirror`: false
+    /// - `remove_footers`: false
+    /// - `footer_patterns`: preprint, conference, and proceedings notices
+    /// - `section_detector`: None
+    /// - `include_elements`: `false`, so `Section::elements` stays empty.
+    /// - `dpi`: 72
+    /// - `keep_intermediate_files`: `true`, so the files stay until `clean_files` is called.
+    /// - `min_table_confidence`: 0.0, so every detected table is kept.
+    /// - `caption_patterns`: English patterns for "Figure", "Fig.", "Table", and "Tab.".
+    /// - `section_keywords`: "abstract", "introduction", "related work", "related works", "experiments", "conclusion", and "references".
+    /// - `dictionary_hyphenation`: `false`, so a hyphen before a lowercase continuation is always removed.
+    /// - `track_provenance`: `false`, so `Section::spans` stays empty.
     ///
     /// # Returns
     ///
     /// A new `ParserConfig` instance with the initialized fields.
     pub fn new() -> ParserConfig {
-        let mut rng = rand::thread_rng();
-        let random_value = rng.gen_range(10000..99999);
-        let mut pdf_path = String::new();
-        pdf_path.push_str("/tmp/pdf_");
-        pdf_path.push_str(&random_value.to_string());
-        pdf_path.push_str(".pdf");
+        // the default temporary directory and DPI are always valid
+        return ParserConfigBuilder::new().build().unwrap();
+    }
 
-        let pdf_figures = HashMap::new();
-        let pdf_html_path = pdf_path.clone().replace(".pdf", ".text.html");
-        let pdf_raw_html_path = pdf_path.clone().replace(".pdf", ".xml");
-        let sections = Vec::new();
-        ParserConfig {
-            pdf_path: pdf_path,
-            pdf_text_path: pdf_html_path,
-            pdf_figures: pdf_figures,
-            pdf_xml_path: pdf_raw_html_path,
-            sections: sections,
-            pdf_info: HashMap::new(),
+    /// Returns a `ParserConfigBuilder` to configure the temporary directory and the rendering.
+    ///
+    /// # Returns
+    ///
+    /// A new `ParserConfigBuilder` instance with the same defaults as `ParserConfig::new`.
+    pub fn builder() -> ParserConfigBuilder {
+        return ParserConfigBuilder::new();
+    }
+
+    /// Returns the zoom factor at which the pages are rendered, i.e. `dpi / 72`.
+    ///
+    /// # Returns
+    ///
+    /// An `f32` representing the zoom factor.
+    pub fn render_zoom(&self) -> f32 {
+        return self.dpi as f32 / 72.0;
+    }
+
+    /// Checks that the poppler commands used for parsing are installed.
+    ///
+    /// Each of `pdfinfo`, `pdftocairo`, `pdftohtml`, and `pdftotext` is run with `--help`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if every command can be run, or an `Err` containing the names of the missing commands.
+    pub fn check_dependencies() -> Result<(), Vec<String>> {
+        return ParserConfig::check_dependencies_in(&std::env::var_os("PATH").unwrap_or_default());
+    }
+
+    /// Checks that the poppler commands used for parsing are installed in the given search path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The search path in the format of the `PATH` environment variable.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if every command can be run, or an `Err` containing the names of the missing commands.
+    pub fn check_dependencies_in(path: &std::ffi::OsStr) -> Result<(), Vec<String>> {
+        let missing = POPPLER_COMMANDS
+            .iter()
+            .filter(|command| {
+                std::process::Command::new(command)
+                    .arg("--help")
+                    .env("PATH", path)
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status()
+                    .is_err()
+            })
+            .map(|command| command.to_string())
+            .collect::<Vec<String>>();
+        if missing.is_empty() {
+            return Ok(());
         }
+        return Err(missing);
     }
 
     /// Returns the width of the PDF page.
@@ -104,6 +237,33 @@ impl ParserConfig {
         return self.pdf_info.get("page_height").unwrap().parse::<i32>().unwrap();
     }
 
+    /// Rewrites a URL with `url_rewrites` and, if enabled, the arXiv mirror.
+    ///
+    /// The first rewrite whose prefix matches the URL is applied. When `use_arxiv_mirror`
+    /// is set, "https://arxiv.org/" is then replaced with "https://export.arxiv.org/".
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to rewrite.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the URL to download from.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        let mut url = url.to_string();
+        if let Some((from, to)) = self.url_rewrites.iter().find(|(from, _)| url.starts_with(from)) {
+            url = format!("{}{}", to, &url[from.len()..]);
+        }
+        if self.use_arxiv_mirror {
+            for prefix in ["https://arxiv.org/", "http://arxiv.org/"] {
+                if url.starts_with(prefix) {
+                    url = format!("https://export.arxiv.org/{}", &url[prefix.len()..]);
+                }
+            }
+        }
+        return url;
+    }
+
     /// Cleans up the generated files associated with the `ParserConfig` instance.
     ///
     /// This function removes the following files if they exist:
@@ -136,6 +296,377 @@ impl ParserConfig {
         }
         return Ok(());
     }
+
+    /// Returns the arXiv identifier of the parsed document, e.g. "1706.03762".
+    ///
+    /// The identifier is read from the arXiv watermark by `parse` and stored in `pdf_info`
+    /// under "arxiv_id"; see `extract_arxiv_id`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the identifier, or `None` if the document has no arXiv watermark
+    /// or has not been parsed yet.
+    pub fn arxiv_id(&self) -> Option<String> {
+        return self.pdf_info.get("arxiv_id").cloned();
+    }
+
+    /// Returns the title of the paper.
+    ///
+    /// The title in the `pdfinfo` metadata is used if it looks like a real title, and the
+    /// largest text on the upper half of the first page otherwise (see `recover_title`).
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - The parsed pages of the PDF document.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the title, or `None` if neither source has one.
+    pub fn paper_title(&self, pages: &Vec<Page>) -> Option<String> {
+        return self
+            .pdf_info
+            .get("title")
+            .map(|title| title.trim().to_string())
+            .filter(|title| is_plausible_title(title))
+            .or_else(|| recover_title(pages));
+    }
+
+    /// Crops the figure regions of the pages from the page images and writes them as PNG files.
+    ///
+    /// The regions are those of `Page::figure_regions` with `caption_patterns`, cropped from the page images in
+    /// `pdf_figures`, so the images must not have been removed by `clean_files` or by
+    /// disabling `keep_intermediate_files`. The files are named `figure_<page>_<n>.png`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - The parsed pages of the PDF document.
+    /// * `out_dir` - The directory in which the figures are written; it is created if missing.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the paths of the written files.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a page with figures has no page image, or if
+    /// reading, cropping, or writing an image fails.
+    pub fn export_figures(&self, pages: &Vec<Page>, out_dir: &Path) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(out_dir)?;
+        let mut paths = Vec::new();
+        for page in pages {
+            let regions = page.figure_regions_with_patterns(&self.caption_patterns);
+            if regions.is_empty() {
+                continue;
+            }
+            let image_path = match self.pdf_figures.get(&page.page_nubmer) {
+                Some(path) if Path::new(path).exists() => path,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Error: page image of page {} not found",
+                        page.page_nubmer
+                    ))
+                }
+            };
+            let image = imgcodecs::imread(image_path, imgcodecs::IMREAD_COLOR)?;
+            if image.cols() == 0 || image.rows() == 0 {
+                return Err(anyhow::anyhow!("Error: failed to read {}", image_path));
+            }
+            let scale_x = image.cols() as f32 / page.width;
+            let scale_y = image.rows() as f32 / page.height;
+            for (i, region) in regions.iter().enumerate() {
+                let x1 = ((region.top_left.x * scale_x) as i32).clamp(0, image.cols());
+                let y1 = ((region.top_left.y * scale_y) as i32).clamp(0, image.rows());
+                let x2 = ((region.bottom_right.x * scale_x).ceil() as i32).clamp(0, image.cols());
+                let y2 = ((region.bottom_right.y * scale_y).ceil() as i32).clamp(0, image.rows());
+                if x2 <= x1 || y2 <= y1 {
+                    continue;
+                }
+                let roi = Mat::roi(&image, Rect::new(x1, y1, x2 - x1, y2 - y1))?;
+                let path = out_dir.join(format!("figure_{}_{}.png", page.page_nubmer, i + 1));
+                imgcodecs::imwrite_def(path.to_str().unwrap(), &roi.try_clone()?)?;
+                paths.push(path);
+            }
+        }
+        return Ok(paths);
+    }
+}
+
+/// `ParserConfigBuilder` builds a `ParserConfig` with a custom temporary directory and rendering.
+///
+/// # Fields
+///
+/// * `temp_dir` - The directory in which the PDF, the HTML and XML files, and the page images are written.
+/// * `dpi` - The resolution in DPI at which the pages are rendered.
+/// * `keep_intermediate_files` - Whether the intermediate files are kept after `parse`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rsrpp::parser::structs::ParserConfig;
+/// let config = ParserConfig::builder().temp_dir("/var/tmp".into()).dpi(144).build().unwrap();
+/// assert!(config.pdf_path.starts_with("/var/tmp/pdf_"));
+/// assert_eq!(config.render_zoom(), 2.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserConfigBuilder {
+    pub temp_dir: PathBuf,
+    pub dpi: u32,
+    pub keep_intermediate_files: bool,
+}
+
+impl ParserConfigBuilder {
+    /// Creates a new `ParserConfigBuilder` instance with the default settings.
+    ///
+    /// The default settings are:
+    /// - `temp_dir`: "/tmp"
+    /// - `dpi`: 72
+    /// - `keep_intermediate_files`: `true`
+    ///
+    /// # Returns
+    ///
+    /// A new `ParserConfigBuilder` instance.
+    pub fn new() -> ParserConfigBuilder {
+        return ParserConfigBuilder {
+            temp_dir: PathBuf::from("/tmp"),
+            dpi: 72,
+            keep_intermediate_files: true,
+        };
+    }
+
+    /// Sets the directory in which the intermediate files are written.
+    pub fn temp_dir(mut self, temp_dir: PathBuf) -> ParserConfigBuilder {
+        self.temp_dir = temp_dir;
+        return self;
+    }
+
+    /// Sets the resolution in DPI at which the pages are rendered; it must be positive.
+    pub fn dpi(mut self, dpi: u32) -> ParserConfigBuilder {
+        self.dpi = dpi;
+        return self;
+    }
+
+    /// Sets whether the intermediate files are kept after `parse`.
+    pub fn keep_intermediate_files(mut self, keep_intermediate_files: bool) -> ParserConfigBuilder {
+        self.keep_intermediate_files = keep_intermediate_files;
+        return self;
+    }
+
+    /// Builds a `ParserConfig` instance.
+    ///
+    /// The other fields are initialized as documented in `ParserConfig::new`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new `ParserConfig` instance, or `ParseError::InvalidConfig`
+    /// if the DPI is 0 or the temporary directory is not valid UTF-8.
+    pub fn build(self) -> Result<ParserConfig, ParseError> {
+        if self.dpi == 0 {
+            return Err(ParseError::InvalidConfig(
+                "dpi must be positive".to_string(),
+            ));
+        }
+        let mut rng = rand::thread_rng();
+        let random_value = rng.gen_range(10000..99999);
+        let pdf_path = self.temp_dir.join(format!("pdf_{}.pdf", random_value));
+        let to_string = |path: PathBuf| -> Result<String, ParseError> {
+            return path.into_os_string().into_string().map_err(|path| {
+                ParseError::InvalidConfig(format!("temp_dir is not valid UTF-8: {:?}", path))
+            });
+        };
+
+        let pdf_figures = HashMap::new();
+        let pdf_html_path = to_string(pdf_path.with_extension("text.html"))?;
+        let pdf_raw_html_path = to_string(pdf_path.with_extension("xml"))?;
+        let pdf_path = to_string(pdf_path)?;
+        let sections = Vec::new();
+        return Ok(ParserConfig {
+            pdf_path: pdf_path,
+            pdf_text_path: pdf_html_path,
+            pdf_figures: pdf_figures,
+            pdf_xml_path: pdf_raw_html_path,
+            sections: sections,
+            pdf_info: HashMap::new(),
+            include_block_types: false,
+            fix_char_spacing: false,
+            diagnostics: Vec::new(),
+            table_containment_threshold: 0.3,
+            normalize_punctuation: false,
+            section_aliases: BTreeMap::new(),
+            drop_sections: Vec::new(),
+            language: None,
+            coordinates_in_points: false,
+            embed_page_images: false,
+            min_block_width_ratio: 0.3,
+            min_block_lines: 4,
+            url_rewrites: Vec::new(),
+            use_arxiv_mirror: false,
+            remove_footers: false,
+            footer_patterns: vec![
+                r"(?i)^preprint\.".to_string(),
+                r"(?i)^\d+(st|nd|rd|th) (annual )?conference on ".to_string(),
+                r"(?i)^(published|accepted|under review) (as a conference paper )?at ".to_string(),
+                r"(?i)^proceedings of the ".to_string(),
+            ],
+            section_detector: None,
+            include_elements: false,
+            dpi: self.dpi,
+            keep_intermediate_files: self.keep_intermediate_files,
+            min_table_confidence: 0.0,
+            caption_patterns: CaptionPatterns::default(),
+            section_keywords: vec![
+                "abstract".to_string(),
+                "introduction".to_string(),
+                "related work".to_string(),
+                "related works".to_string(),
+                "experiments".to_string(),
+                "conclusion".to_string(),
+                "references".to_string(),
+            ],
+            dictionary_hyphenation: false,
+            track_provenance: false,
+        });
+    }
+}
+
+/// The `SectionDetector` struct wraps a user-provided function that detects section titles.
+///
+/// The function receives the XML generated by `pdftohtml -xml`: a `<pdf2xml>` root with one
+/// `<page number="N" width=".." height="..">` element per page, which contains `<fontspec id=".."
+/// size=".." family=".." color=".."/>` elements and `<text top=".." left=".." width=".."
+/// height=".." font="..">` elements holding the text (with inline `<b>`/`<i>` markup). It
+/// returns the page number and the title of each section in document order.
+#[derive(Clone)]
+pub struct SectionDetector(pub Arc<dyn Fn(&str) -> Vec<(PageNumber, String)> + Send + Sync>);
+
+impl SectionDetector {
+    /// Creates a new `SectionDetector` instance from a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `detector` - A function taking the `pdftohtml` XML and returning the sections.
+    ///
+    /// # Returns
+    ///
+    /// A new `SectionDetector` instance.
+    pub fn new<F>(detector: F) -> SectionDetector
+    where
+        F: Fn(&str) -> Vec<(PageNumber, String)> + Send + Sync + 'static,
+    {
+        return SectionDetector(Arc::new(detector));
+    }
+
+    /// Detects the sections of a document from its `pdftohtml` XML.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The XML generated by `pdftohtml`.
+    ///
+    /// # Returns
+    ///
+    /// A vector of tuples containing page numbers and section titles.
+    pub fn detect(&self, xml: &str) -> Vec<(PageNumber, String)> {
+        return (self.0)(xml);
+    }
+}
+
+impl std::fmt::Debug for SectionDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "SectionDetector(..)");
+    }
+}
+
+impl PartialEq for SectionDetector {
+    fn eq(&self, other: &SectionDetector) -> bool {
+        return Arc::ptr_eq(&self.0, &other.0);
+    }
+}
+
+/// The `FontSpec` struct represents a `<fontspec>` element of the `pdftohtml` XML.
+///
+/// # Fields
+///
+/// * `id` - The identifier referenced by the `font` attribute of `<text>` elements.
+/// * `size` - The font size in points.
+/// * `family` - The font family, e.g. "Times" or "NimbusRomNo9L-Medi".
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontSpec {
+    pub id: i32,
+    pub size: f32,
+    pub family: String,
+}
+
+impl FontSpec {
+    /// Returns whether the font family names a bold weight, e.g. "Times-Bold" or "NimbusRomNo9L-Medi".
+    pub fn is_bold(&self) -> bool {
+        let family = self.family.to_lowercase();
+        return ["bold", "medi", "semibold", "demi", "black", "heavy"]
+            .iter()
+            .any(|weight| family.contains(weight));
+    }
+}
+
+/// The `XmlText` struct represents a `<text>` element of the `pdftohtml` XML.
+///
+/// # Fields
+///
+/// * `page_number` - The number of the page containing the element.
+/// * `top` - The y-coordinate of the top edge of the element.
+/// * `left` - The x-coordinate of the left edge of the element.
+/// * `width` - The width of the element.
+/// * `font` - The identifier of the `FontSpec` of the element.
+/// * `bold` - Whether all of the text is set in bold, by `<b>` markup or a bold font family.
+/// * `text` - The text of the element with the inline markup removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlText {
+    pub page_number: PageNumber,
+    pub top: f32,
+    pub left: f32,
+    pub width: f32,
+    pub font: i32,
+    pub bold: bool,
+    pub text: String,
+}
+
+/// The `ParseDiagnostic` enum represents a suspicious result detected while parsing a PDF document.
+///
+/// # Variants
+///
+/// * `LongSection` - A section spans an unusually large number of pages.
+/// * `MissingReferences` - No References heading was detected.
+/// * `LowColumnConfidence` - Most blocks of a page cross a column boundary.
+/// * `PhantomSection` - A section title was detected by font but no block was assigned to it.
+/// * `TablesSkipped` - Table detection was skipped on a page, e.g. because its image could not be read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParseDiagnostic {
+    LongSection { title: String, pages: usize },
+    MissingReferences,
+    LowColumnConfidence { page: PageNumber },
+    PhantomSection { title: String, page: PageNumber },
+    TablesSkipped { page: PageNumber, reason: String },
+}
+
+impl std::fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseDiagnostic::LongSection { title, pages } => {
+                write!(f, "section {} spans {} pages (suspicious)", title, pages)
+            }
+            ParseDiagnostic::MissingReferences => write!(f, "no References heading detected"),
+            ParseDiagnostic::LowColumnConfidence { page } => {
+                write!(f, "column detection low confidence on page {}", page)
+            }
+            ParseDiagnostic::PhantomSection { title, page } => {
+                write!(
+                    f,
+                    "section {} on page {} has no content (dropped)",
+                    title, page
+                )
+            }
+            ParseDiagnostic::TablesSkipped { page, reason } => {
+                write!(f, "table detection skipped on page {}: {}", page, reason)
+            }
+        }
+    }
 }
 
 /// The `Word` struct represents a word in a PDF document.
@@ -146,13 +677,16 @@ impl ParserConfig {
 /// * `x` - The x-coordinate of the top-left corner of the word.
 /// * `y` - The y-coordinate of the top-left corner of the word.
 /// * `width` - The width of the word.
-#[derive(Debug, Clone, PartialEq)]
+/// * `confidence` - The recognition confidence from 0.0 to 1.0 for OCR words; `None` for words extracted by poppler.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Word {
     pub text: String,
     pub x: f32,
     pub y: f32,
     pub width: f32,
     pub height: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
 }
 
 impl Word {
@@ -170,7 +704,7 @@ impl Word {
 /// * `y` - The y-coordinate of the top-left corner of the line.
 /// * `width` - The width of the line.
 /// * `height` - The height of the line.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Line {
     pub words: Vec<Word>,
     pub x: f32,
@@ -217,8 +751,41 @@ impl Line {
             y: y,
             width: width,
             height: height,
+            confidence: None,
         });
     }
+    /// Returns the lowest confidence of the words in the `Line`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the lowest word confidence, or `None` if no word has a confidence.
+    pub fn min_confidence(&self) -> Option<f32> {
+        return self.words.iter().filter_map(|word| word.confidence).reduce(f32::min);
+    }
+    /// Returns whether the `Line` starts with a footnote marker.
+    ///
+    /// A marker is a number of one or two digits or a symbol such as "∗", "†", or "‡", either
+    /// as a word of its own or fused to the first word, or any first word set as a superscript.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the first word of the line is a footnote marker.
+    pub fn starts_with_footnote_marker(&self) -> bool {
+        let first = match self.words.first() {
+            Some(first) => first,
+            None => return false,
+        };
+        let font_size = self.words.iter().skip(1).map(|word| word.font_size()).fold(0.0, f32::max);
+        if first.font_size() < font_size * 0.8 {
+            return true;
+        }
+        let digits = first.text.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 {
+            return digits <= 2;
+        }
+        return first.text.starts_with(['*', '∗', '†', '‡', '§', '¶', '‖', '#']);
+    }
+
     /// Returns the concatenated text of all `Word` instances in the `Line`.
     ///
     /// # Returns
@@ -231,6 +798,100 @@ impl Line {
         }
         return words.join(" ");
     }
+
+    /// Re-joins lines in which every glyph was emitted as a separate `Word`.
+    ///
+    /// Some PDF encoders emit each character as its own word, so `get_text` returns
+    /// "T h e   m o d e l". When the median word length of the line is 1, consecutive
+    /// words are merged into a single word unless the horizontal gap between them is
+    /// larger than the median gap by more than 15% of the font size, which is treated as a space.
+    pub fn fix_char_spacing(&mut self) {
+        if self.words.len() < 2 {
+            return;
+        }
+        let lengths = self.words.iter().map(|word| word.text.chars().count() as f32);
+        let median_length: f32 = sci_rs::stats::median(lengths).0;
+        if median_length > 1.0 {
+            return;
+        }
+
+        let gaps = self.words.windows(2).map(|pair| pair[1].x - (pair[0].x + pair[0].width));
+        let median_gap: f32 = sci_rs::stats::median(gaps).0;
+        let font_size: f32 = sci_rs::stats::median(self.words.iter().map(|w| w.font_size())).0;
+        let space_gap = median_gap + font_size * 0.15;
+
+        let mut words: Vec<Word> = Vec::new();
+        for word in self.words.drain(..) {
+            match words.last_mut() {
+                Some(last) if word.x - (last.x + last.width) <= space_gap => {
+                    let bottom = f32::max(last.y + last.height, word.y + word.height);
+                    last.text.push_str(&word.text);
+                    last.width = word.x + word.width - last.x;
+                    last.y = f32::min(last.y, word.y);
+                    last.height = bottom - last.y;
+                }
+                _ => words.push(word),
+            }
+        }
+        self.words = words;
+    }
+}
+
+/// The `BlockType` enum represents the role of a block in a PDF document.
+///
+/// # Variants
+///
+/// * `Body` - A block of body text.
+/// * `Caption` - A figure or table caption, e.g. "Figure 1: ...".
+/// * `Header` - A section heading, e.g. "3 Model Architecture".
+/// * `Footnote` - A footnote set in a smaller font at the bottom of a page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BlockType {
+    Body,
+    Caption,
+    Header,
+    Footnote,
+}
+
+/// The `SectionElement` enum represents an entry of a section in reading order.
+///
+/// # Variants
+///
+/// * `Paragraph` - An entry of `Section::contents`.
+/// * `Caption` - The full text of an entry of `Section::captions`, e.g. "Figure 1: ...".
+/// * `Equation` - A display equation; equations are not detected yet, so this variant is not produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SectionElement {
+    Paragraph(String),
+    Caption(String),
+    Equation(String),
+}
+
+/// The `ClassifiedBlock` struct represents a block of a section with its `BlockType`.
+///
+/// # Fields
+///
+/// * `text` - The text of the block; for a caption, the full text including the label.
+/// * `block_type` - The role of the block, e.g. `BlockType::Caption`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifiedBlock {
+    pub text: String,
+    pub block_type: BlockType,
+}
+
+/// The `TextSpan` struct records where an entry of `Section::contents` comes from.
+///
+/// # Fields
+///
+/// * `page` - The number of the page containing the source block.
+/// * `coord` - The bounding box of the source block on the page.
+/// * `text_range` - The byte range of the content entry in `Section::get_text`; a paragraph
+///   continued across blocks has one span per block, all covering the whole entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub page: PageNumber,
+    pub coord: Coordinate,
+    pub text_range: (usize, usize),
 }
 
 /// The `Block` struct represents a block of text in a PDF document.
@@ -243,7 +904,8 @@ impl Line {
 /// * `width` - The width of the block.
 /// * `height` - The height of the block.
 /// * `section` - The section of the document to which the block belongs.
-#[derive(Debug, Clone, PartialEq)]
+/// * `block_type` - The role of the block in the document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     pub lines: Vec<Line>,
     pub x: f32,
@@ -251,6 +913,7 @@ pub struct Block {
     pub width: f32,
     pub height: f32,
     pub section: String,
+    pub block_type: BlockType,
 }
 
 impl Block {
@@ -274,6 +937,7 @@ impl Block {
             width: width,
             height: height,
             section: String::new(),
+            block_type: BlockType::Body,
         }
     }
     /// Adds a new `Line` to the `Block`.
@@ -290,49 +954,403 @@ impl Block {
 
     /// Returns the concatenated text of all `Line` instances in the `Block`.
     ///
+    /// A line ending with an ASCII hyphen-minus is treated as a word break and joined to
+    /// the next line without the hyphen when the next line starts with a lowercase letter
+    /// (e.g. "chal-" + "lenges"). When the next line does not start lowercase the hyphen
+    /// is kept and the lines are joined without a space (e.g. "COVID-" + "19").
+    /// En/em dashes (U+2013/U+2014) and spaced hyphens are never treated as word breaks.
+    ///
     /// # Returns
     ///
     /// A `String` containing the text of all lines in the block, with hyphenated line endings removed.
     pub fn get_text(&self) -> String {
         let mut text = String::new();
         for line in &self.lines {
-            text = text.trim().trim_end_matches("-").to_string();
-            text.push_str(" ");
-            text.push_str(&line.get_text());
+            text = join_lines(text.trim(), &line.get_text(), false);
         }
         return text.trim().to_string();
     }
-}
-
-/// The `Page` struct represents a page in a PDF document.
-///
-/// # Fields
-///
-/// * `blocks` - A vector of `Block` structs that make up the page.
-/// * `width` - The width of the page.
-/// * `height` - The height of the page.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Page {
-    pub blocks: Vec<Block>,
-    pub width: f32,
-    pub height: f32,
-    pub tables: Vec<Coordinate>,
-    pub page_nubmer: PageNumber,
-    pub number_of_columns: i8,
-}
 
-impl Page {
-    /// Creates a new `Page` instance.
-    ///
-    /// # Arguments
+    /// Returns the concatenated text of all `Line` instances in the `Block`, keeping lexical hyphens.
     ///
-    /// * `width` - The width of the page.
-    /// * `height` - The height of the page.
-    /// * `page_number` - The page number.
+    /// Like `get_text`, but a hyphen at the end of a line before a lowercase continuation is
+    /// only removed when the joined word is a known English word (see `is_english_word`), so
+    /// "chal-" + "lenges" becomes "challenges" while "state-" + "of-the-art" stays
+    /// "state-of-the-art".
     ///
     /// # Returns
     ///
-    /// A new `Page` instance with the specified dimensions and page number.
+    /// A `String` containing the text of all lines in the block.
+    pub fn get_text_with_dictionary(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            text = join_lines(text.trim(), &line.get_text(), true);
+        }
+        return text.trim().to_string();
+    }
+
+    /// Returns whether the `Block` looks like a section heading, independent of the document's title font.
+    ///
+    /// A heading has at most two lines and twelve words, does not end with a period, and is
+    /// either set at least 10% larger than the body text or starts with a section number such
+    /// as "3.1" or an appendix number such as "A.2". Font weight is not available from
+    /// `pdftotext`, so bold headings set at the body size are only recognized when they are
+    /// numbered; a bare appendix letter as in "A Proofs" is only recognized by its font size,
+    /// since "A New Approach" reads the same.
+    ///
+    /// # Arguments
+    ///
+    /// * `body_font` - The font size of the body text, e.g. the median word height.
+    ///
+    /// # Returns
+    ///
+    /// A `bool` indicating whether the block is likely a heading.
+    pub fn looks_like_heading(&self, body_font: f32) -> bool {
+        let text = self.get_text();
+        let words = text.split_whitespace().count();
+        if words == 0 || words > 12 || self.lines.len() > 2 || text.ends_with('.') {
+            return false;
+        }
+        let font_size = self
+            .lines
+            .iter()
+            .flat_map(|line| line.words.iter())
+            .map(|word| word.font_size())
+            .fold(0.0, f32::max);
+        return font_size >= body_font * 1.1 || HEADING_NUMBER_REGEX.is_match(&text);
+    }
+
+    /// Splits a footnote `Block` into one `Block` per footnote.
+    ///
+    /// A footnote starts at each line with a footnote marker, see `Line::starts_with_footnote_marker`,
+    /// and the lines without a marker continue the footnote before them.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `Block` instances with the section and type of the block, one per footnote.
+    pub fn split_footnotes(&self) -> Vec<Block> {
+        let mut footnotes: Vec<Block> = Vec::new();
+        for line in self.lines.iter() {
+            match footnotes.last_mut() {
+                Some(footnote) if !line.starts_with_footnote_marker() => {
+                    footnote.height = line.y + line.height - footnote.y;
+                    footnote.lines.push(line.clone());
+                }
+                _ => {
+                    let mut footnote = Block::new(self.x, line.y, self.width, line.height);
+                    footnote.section = self.section.clone();
+                    footnote.block_type = self.block_type.clone();
+                    footnote.lines.push(line.clone());
+                    footnotes.push(footnote);
+                }
+            }
+        }
+        return footnotes;
+    }
+
+    /// Splits a keywords line such as "Keywords: a; b; c" and the lines after it off the `Block`.
+    ///
+    /// Lines starting with "Keywords", "Key words", or "Index Terms" are recognized case-insensitively.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the block without the keywords lines and the parsed keywords, or `None` if the block has no keywords line.
+    pub fn split_keywords(&self) -> Option<(Block, Vec<String>)> {
+        let start =
+            self.lines.iter().position(|line| KEYWORDS_LINE_REGEX.is_match(&line.get_text()))?;
+        let mut prose = self.clone();
+        let mut keywords = self.clone();
+        keywords.lines = prose.lines.split_off(start);
+        return Some((prose, parse_keywords(&keywords.get_text())));
+    }
+}
+
+/// Parses the keywords of a keywords line such as "Keywords: a; b; c" or "Index Terms—a, b, c".
+///
+/// Keywords are separated by semicolons, or by commas when there is no semicolon.
+///
+/// # Arguments
+///
+/// * `text` - The text of the keywords line.
+///
+/// # Returns
+///
+/// A vector of keywords in order of appearance.
+pub fn parse_keywords(text: &str) -> Vec<String> {
+    let prefix_regex = regex::Regex::new(
+        r"(?i)^\s*(keywords|key\s+words|index\s+terms)\s*[:.\-\u{2013}\u{2014}]*\s*",
+    )
+    .unwrap();
+    let text = prefix_regex.replace(text, "");
+    let separator = if text.contains(';') { ';' } else { ',' };
+    return text
+        .split(|c: char| c == separator || c == '\u{b7}' || c == '\u{2022}')
+        .map(|keyword| keyword.trim().trim_end_matches('.').trim().to_string())
+        .filter(|keyword| !keyword.is_empty())
+        .collect();
+}
+
+/// Joins two consecutive lines of text, resolving a trailing hyphen on the first line.
+///
+/// # Arguments
+///
+/// * `text` - The text accumulated so far.
+/// * `next` - The text of the next line.
+/// * `dictionary` - Whether the hyphen is kept when the joined word is not a known English word.
+///
+/// # Returns
+///
+/// A `String` containing the joined text.
+fn join_lines(text: &str, next: &str, dictionary: bool) -> String {
+    let next = next.trim();
+    if text.is_empty() {
+        return next.to_string();
+    }
+
+    let mut chars = text.chars().rev();
+    let is_word_break =
+        chars.next() == Some('-') && chars.next().map(|c| c.is_alphanumeric()).unwrap_or(false);
+    if !is_word_break {
+        return format!("{} {}", text, next);
+    }
+
+    let starts_lowercase = next.chars().next().map(|c| c.is_lowercase()).unwrap_or(false);
+    if starts_lowercase {
+        let head = text.trim_end_matches('-');
+        if dictionary {
+            let prefix =
+                head.chars().rev().take_while(|c| c.is_alphabetic()).collect::<Vec<char>>();
+            let prefix = prefix.iter().rev().collect::<String>();
+            let suffix = next.chars().take_while(|c| c.is_alphabetic()).collect::<String>();
+            if !is_english_word(&format!("{}{}", prefix, suffix)) {
+                return format!("{}{}", text, next);
+            }
+        }
+        return format!("{}{}", head, next);
+    } else {
+        return format!("{}{}", text, next);
+    }
+}
+
+/// Returns whether a word is in the embedded English word list.
+///
+/// The list holds lemmas, so common inflections ("challenges", "improved", "learning",
+/// "studies", "largely") are recognized by removing their suffix. Case is ignored.
+///
+/// # Arguments
+///
+/// * `word` - The word to look up.
+///
+/// # Returns
+///
+/// `true` if the word or its lemma is in the list.
+pub fn is_english_word(word: &str) -> bool {
+    static WORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    let words = WORDS.get_or_init(|| {
+        return ENGLISH_WORDS
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+    });
+
+    let word = word.to_lowercase();
+    if words.contains(word.as_str()) {
+        return true;
+    }
+    let suffixes = [
+        ("ies", "y"),
+        ("es", ""),
+        ("s", ""),
+        ("ed", ""),
+        ("ed", "e"),
+        ("ing", ""),
+        ("ing", "e"),
+        ("ly", ""),
+        ("er", ""),
+        ("est", ""),
+    ];
+    return suffixes.iter().any(|(suffix, replacement)| match word.strip_suffix(suffix) {
+        Some(stem) if stem.len() >= 3 => {
+            words.contains(format!("{}{}", stem, replacement).as_str())
+        }
+        _ => false,
+    });
+}
+
+/// A leading section index such as "3 ", "2.1 " or "4. ", followed by whitespace.
+static SECTION_NUMBER_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^\d+(\.\d+)*\.?\s+").unwrap());
+
+/// A leading section number such as "3", "3.1." or "A.2" followed by a capitalized title.
+static HEADING_NUMBER_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^(\d+|[A-Z]\.\d+)(\.\d+)*\.?\s+\p{Lu}").unwrap());
+
+/// The start of a keywords line, e.g. "Keywords:", "Key words" or "Index Terms".
+static KEYWORDS_LINE_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"(?i)^\s*(keywords|key\s+words|index\s+terms)\b").unwrap());
+
+/// The marker of a numbered reference, e.g. "[12]".
+pub(crate) static REFERENCE_MARKER_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\[\d+\]").unwrap());
+
+/// Removes a leading section index such as "3 ", "2.1 " or "4. " from a title.
+///
+/// Only an index at the start of the title followed by whitespace is removed, so numbers
+/// that are part of the title ("3D Reconstruction", "2.5D Vision") are preserved.
+///
+/// # Arguments
+///
+/// * `title` - The title to strip.
+///
+/// # Returns
+///
+/// A `String` containing the trimmed title without its section index.
+pub fn strip_section_number(title: &str) -> String {
+    return SECTION_NUMBER_REGEX.replace(title.trim(), "").trim().to_string();
+}
+
+/// Normalizes a string for fuzzy matching.
+///
+/// The text is lowercased, every character that is not alphanumeric is replaced by a
+/// space, and runs of whitespace are collapsed into a single space.
+///
+/// # Arguments
+///
+/// * `text` - The text to normalize.
+///
+/// # Returns
+///
+/// A `String` containing the normalized text.
+pub fn normalize_for_matching(text: &str) -> String {
+    let text = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>();
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+/// Computes the similarity of two strings as the Jaccard index of their character trigrams.
+///
+/// Both strings are normalized with `normalize_for_matching` first. A string shorter than
+/// three characters is treated as a single trigram.
+///
+/// # Arguments
+///
+/// * `a` - The first string.
+/// * `b` - The second string.
+///
+/// # Returns
+///
+/// A `f64` between 0.0 (no shared trigram) and 1.0 (identical trigram sets).
+///
+/// # Examples
+///
+/// ```
+/// use rsrpp::parser::structs::trigram_similarity;
+///
+/// assert_eq!(trigram_similarity("Attention Is All You Need", "attention is all you need."), 1.0);
+/// assert!(trigram_similarity("Attention Is All You Need", "Attention is all you need?!") > 0.9);
+/// assert!(trigram_similarity("Attention Is All You Need", "Deep Residual Learning") < 0.2);
+/// ```
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    let trigrams = |text: &str| {
+        let chars = normalize_for_matching(text).chars().collect::<Vec<char>>();
+        let mut grams = HashSet::new();
+        if chars.len() < 3 {
+            if !chars.is_empty() {
+                grams.insert(chars.iter().collect::<String>());
+            }
+        } else {
+            for gram in chars.windows(3) {
+                grams.insert(gram.iter().collect::<String>());
+            }
+        }
+        grams
+    };
+    let a = trigrams(a);
+    let b = trigrams(b);
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    return a.intersection(&b).count() as f64 / union as f64;
+}
+
+/// Normalizes smart quotes and dashes to ASCII punctuation.
+///
+/// Single quotes (U+2018, U+2019, U+201A, U+201B) become `'`, double quotes
+/// (U+201C, U+201D, U+201E, U+201F) become `"`, an en dash (U+2013) becomes `-` and
+/// an em dash (U+2014) becomes `--`. Text inside `<math>...</math>` tags is left untouched.
+///
+/// # Arguments
+///
+/// * `text` - The text to normalize.
+///
+/// # Returns
+///
+/// A `String` containing the normalized text.
+pub fn normalize_punctuation_ascii(text: &str) -> String {
+    let math_regex = regex::Regex::new(r"(?s)<math\b[^>]*>.*?</math>").unwrap();
+    let normalize = |prose: &str, normalized: &mut String| {
+        for c in prose.chars() {
+            match c {
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => normalized.push('\''),
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => normalized.push('"'),
+                '\u{2013}' => normalized.push('-'),
+                '\u{2014}' => normalized.push_str("--"),
+                _ => normalized.push(c),
+            }
+        }
+    };
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut last = 0;
+    for math in math_regex.find_iter(text) {
+        normalize(&text[last..math.start()], &mut normalized);
+        normalized.push_str(math.as_str());
+        last = math.end();
+    }
+    normalize(&text[last..], &mut normalized);
+    return normalized;
+}
+
+/// The `Page` struct represents a page in a PDF document.
+///
+/// # Fields
+///
+/// * `blocks` - A vector of `Block` structs that make up the page.
+/// * `width` - The width of the page.
+/// * `height` - The height of the page.
+/// * `image_data` - The base64-encoded JPEG image of the page; set only with `ParserConfig::embed_page_images`.
+/// * `captioned_figures` - The figure and table captions of the page paired with the regions they describe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    pub blocks: Vec<Block>,
+    pub width: f32,
+    pub height: f32,
+    pub tables: Vec<DetectedTable>,
+    pub page_nubmer: PageNumber,
+    pub number_of_columns: i8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captioned_figures: Vec<CaptionedFigure>,
+}
+
+impl Page {
+    /// Creates a new `Page` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the page.
+    /// * `height` - The height of the page.
+    /// * `page_number` - The page number.
+    ///
+    /// # Returns
+    ///
+    /// A new `Page` instance with the specified dimensions and page number.
     pub fn new(width: f32, height: f32, page_number: PageNumber) -> Page {
         Page {
             blocks: Vec::new(),
@@ -341,6 +1359,8 @@ impl Page {
             tables: Vec::new(),
             page_nubmer: page_number,
             number_of_columns: 1,
+            image_data: None,
+            captioned_figures: Vec::new(),
         }
     }
 
@@ -370,6 +1390,172 @@ impl Page {
         return text;
     }
 
+    /// Renders the text of the page as fixed-width text approximating its visual layout.
+    ///
+    /// Lines whose top edges are within half a line height of each other are placed
+    /// on the same row, and each line starts at a column proportional to `Line::x / width`.
+    /// A line that would overlap the previous one on its row is shifted right by one space.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The number of columns corresponding to the page width.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing one row of text per visual line, separated by newlines.
+    pub fn render_text_layout(&self, cols: usize) -> String {
+        let mut lines =
+            self.blocks.iter().flat_map(|block| block.lines.iter()).collect::<Vec<&Line>>();
+        lines.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap().then(a.x.partial_cmp(&b.x).unwrap()));
+
+        let mut rows: Vec<Vec<&Line>> = Vec::new();
+        for line in lines {
+            match rows.last_mut() {
+                Some(row) if line.y - row[0].y < row[0].height / 2.0 => row.push(line),
+                _ => rows.push(vec![line]),
+            }
+        }
+
+        let mut text = Vec::new();
+        for mut row in rows {
+            row.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+            let mut row_text = String::new();
+            for line in row {
+                let offset = (line.x / self.width * cols as f32).round().max(0.0) as usize;
+                let length = row_text.chars().count();
+                if length == 0 {
+                    row_text.push_str(&" ".repeat(offset));
+                } else if length < offset {
+                    row_text.push_str(&" ".repeat(offset - length));
+                } else {
+                    row_text.push(' ');
+                }
+                row_text.push_str(&line.get_text());
+            }
+            text.push(row_text);
+        }
+        return text.join("\n");
+    }
+
+    /// Returns the regions of the figures on the page.
+    ///
+    /// Figures are not text, so their regions are inferred from the layout: a figure caption
+    /// (see `parse_caption`) is preceded by the area between it and the nearest paragraph or
+    /// table above it, within the caption's column or the whole text width for captions that
+    /// span the columns. Blocks narrower than half of that width, e.g. axis labels inside a
+    /// plot, do not bound the area. Areas lower than 20 points are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Coordinate>` of the figure regions, in the order of their captions.
+    pub fn figure_regions(&self) -> Vec<Coordinate> {
+        return self.figure_regions_with_patterns(&CaptionPatterns::default());
+    }
+
+    /// Returns the regions of the figures on the page, recognizing captions with `patterns`.
+    ///
+    /// See `figure_regions` for how the regions are inferred.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - The patterns that recognize figure captions.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Coordinate>` of the figure regions, in the order of their captions.
+    pub fn figure_regions_with_patterns(&self, patterns: &CaptionPatterns) -> Vec<Coordinate> {
+        let mut regions = Vec::new();
+        if self.blocks.is_empty() {
+            return regions;
+        }
+        let text_left = self.blocks.iter().map(|block| block.x).fold(f32::MAX, f32::min);
+        let text_right =
+            self.blocks.iter().map(|block| block.x + block.width).fold(f32::MIN, f32::max);
+        let text_top = self.blocks.iter().map(|block| block.y).fold(f32::MAX, f32::min);
+        let columns = self.number_of_columns.max(1) as f32;
+        let column_width = (text_right - text_left) / columns;
+
+        for caption in self.blocks.iter() {
+            if caption.block_type != BlockType::Caption {
+                continue;
+            }
+            match patterns.parse(&caption.get_text()) {
+                Some((CaptionKind::Figure, _, _)) => {}
+                _ => continue,
+            }
+            let (left, right) = if caption.width > column_width * 1.1 {
+                (text_left, text_right)
+            } else {
+                let center = caption.x + caption.width / 2.0;
+                let column =
+                    ((center - text_left) / column_width).floor().clamp(0.0, columns - 1.0);
+                (
+                    text_left + column * column_width,
+                    text_left + (column + 1.0) * column_width,
+                )
+            };
+            let overlaps = |x1: f32, x2: f32| f32::min(right, x2) - f32::max(left, x1) > 0.0;
+
+            let mut top = text_top;
+            for block in self.blocks.iter() {
+                let bottom = block.y + block.height;
+                if bottom <= caption.y
+                    && overlaps(block.x, block.x + block.width)
+                    && block.width >= (right - left) / 2.0
+                {
+                    top = f32::max(top, bottom);
+                }
+            }
+            for table in self.tables.iter() {
+                if table.coord.bottom_right.y <= caption.y
+                    && overlaps(table.coord.top_left.x, table.coord.bottom_right.x)
+                {
+                    top = f32::max(top, table.coord.bottom_right.y);
+                }
+            }
+            if caption.y - top >= MIN_FIGURE_HEIGHT {
+                regions.push(Coordinate::from_rect(left, top, right, caption.y));
+            }
+        }
+        return regions;
+    }
+
+    /// Converts all coordinates of the page from render pixels into PDF points.
+    ///
+    /// See `Coordinate::to_pdf_points` for the transform.
+    ///
+    /// # Arguments
+    ///
+    /// * `zoom` - The zoom factor at which the page was rendered.
+    pub fn to_pdf_points(&mut self, zoom: f32) {
+        self.width /= zoom;
+        self.height /= zoom;
+        for block in self.blocks.iter_mut() {
+            block.x /= zoom;
+            block.y /= zoom;
+            block.width /= zoom;
+            block.height /= zoom;
+            for line in block.lines.iter_mut() {
+                line.x /= zoom;
+                line.y /= zoom;
+                line.width /= zoom;
+                line.height /= zoom;
+                for word in line.words.iter_mut() {
+                    word.x /= zoom;
+                    word.y /= zoom;
+                    word.width /= zoom;
+                    word.height /= zoom;
+                }
+            }
+        }
+        for table in self.tables.iter_mut() {
+            table.coord = table.coord.to_pdf_points(zoom);
+        }
+        for figure in self.captioned_figures.iter_mut() {
+            figure.region = figure.region.to_pdf_points(zoom);
+        }
+    }
+
     /// Returns the y-coordinate of the topmost line in the page.
     ///
     /// # Returns
@@ -536,6 +1722,29 @@ impl Coordinate {
         return self.bottom_left.y - self.top_left.y;
     }
 
+    /// Converts the rectangle from render pixels into PDF points.
+    ///
+    /// Render pixels are PDF points multiplied by `zoom` (zoom = DPI / 72), so each value is
+    /// divided by `zoom`. The origin stays at the top-left corner of the page with y growing
+    /// downward, as in PDF.js viewports; for the bottom-left origin of PDF user space, use
+    /// `page_height - y` on the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `zoom` - The zoom factor at which the page was rendered.
+    ///
+    /// # Returns
+    ///
+    /// A `Coordinate` instance in PDF points.
+    pub fn to_pdf_points(&self, zoom: f32) -> Coordinate {
+        return Coordinate::from_rect(
+            self.top_left.x / zoom,
+            self.top_left.y / zoom,
+            self.bottom_right.x / zoom,
+            self.bottom_right.y / zoom,
+        );
+    }
+
     /// Determines if the rectangle represented by this `Coordinate` intersects with another `Coordinate`.
     ///
     /// # Arguments
@@ -617,22 +1826,113 @@ impl Coordinate {
     ///
     /// A `bool` indicating whether this rectangle is contained within the other rectangle.
     pub fn is_contained_in(&self, other: &Coordinate) -> bool {
+        return self.is_contained_in_with_threshold(other, 0.3);
+    }
+
+    /// Determines if the rectangle represented by this `Coordinate` is contained within another `Coordinate`,
+    /// using a custom containment threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Another `Coordinate` to check for containment.
+    /// * `threshold` - The minimum fraction of this rectangle's area that must lie inside `other`.
+    ///
+    /// # Returns
+    ///
+    /// A `bool` indicating whether this rectangle is contained within the other rectangle.
+    pub fn is_contained_in_with_threshold(&self, other: &Coordinate, threshold: f32) -> bool {
         let iou = self.iou(other);
         let intersection = self.intersection(other).get_area();
         let self_area = self.get_area();
-        return iou > 0.0 && intersection / self_area > 0.3;
+        return iou > 0.0 && intersection / self_area > threshold;
+    }
+}
+
+/// The `DetectedTable` struct represents a table region detected from the ruling lines of a page image.
+///
+/// # Fields
+///
+/// * `coord` - The coordinates of the table.
+/// * `confidence` - How table-like the region is, from 0.0 to 1.0, based on the number and length of its ruling lines and its aspect ratio.
+/// * `rows` - The cell texts of the table, row by row; empty if no text was found inside the region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedTable {
+    pub coord: Coordinate,
+    pub confidence: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<Vec<String>>,
+}
+
+impl DetectedTable {
+    /// Creates a new `DetectedTable` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `coord` - The coordinates of the table.
+    /// * `confidence` - The confidence of the detection.
+    ///
+    /// # Returns
+    ///
+    /// A new `DetectedTable` instance.
+    pub fn new(coord: Coordinate, confidence: f32) -> DetectedTable {
+        return DetectedTable {
+            coord: coord,
+            confidence: confidence,
+            rows: Vec::new(),
+        };
+    }
+
+    /// Returns the contents of the detected table as a `Table`.
+    ///
+    /// # Returns
+    ///
+    /// A `Table` with the coordinates and rows of the detected table.
+    pub fn to_table(&self) -> Table {
+        return Table {
+            coord: self.coord.clone(),
+            rows: self.rows.clone(),
+        };
     }
 }
 
+/// The `Table` struct represents the contents of a table in a PDF document.
+///
+/// # Fields
+///
+/// * `coord` - The coordinates of the table.
+/// * `rows` - The cell texts of the table, row by row; every row has one entry per column, empty for missing cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub coord: Coordinate,
+    pub rows: Vec<Vec<String>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextBlock {
     pub text: String,
     pub coordinates: Coordinate,
 }
 
+/// The `Reference` struct represents an entry of the References section.
+///
+/// # Fields
+///
+/// * `text` - The full text of the reference.
+/// * `authors` - The author names, split on a best-effort basis.
+/// * `title` - The title, taken on a best-effort basis from the sentence after the authors.
+/// * `year` - The publication year.
+/// * `doi` - The DOI, e.g. "10.18653/v1/N19-1423".
+/// * `arxiv_id` - The arXiv identifier without the version, e.g. "1607.06450".
+/// * `url` - The first URL of the reference.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Reference {
     pub text: String,
-    pub coordinates: Coordinate,
+    pub authors: Vec<String>,
+    pub title: Option<String>,
+    pub year: Option<u16>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub url: Option<String>,
 }
 
 impl TextBlock {
@@ -643,22 +1943,742 @@ impl TextBlock {
         }
     }
 }
-/// The `Section` struct represents a section in a PDF document.
+
+/// The `Citation` struct represents an in-text citation marker.
 ///
 /// # Fields
 ///
-/// * `title` - The title of the section.
-/// * `content` - The content of the section.
+/// * `marker` - The text of the marker, e.g. "[12]" or "Vaswani et al., 2017".
+/// * `section` - The title of the section in which the marker appears.
+/// * `reference_index` - The index of the cited entry in the reference list, if it was found.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Section {
-    pub index: i8,
+pub struct Citation {
+    pub marker: String,
+    pub section: String,
+    pub reference_index: Option<usize>,
+}
+
+/// The `TocEntry` struct represents an entry of a table of contents printed in a PDF document.
+///
+/// # Fields
+///
+/// * `title` - The title of the entry without its section index.
+/// * `page` - The printed page number of the entry.
+/// * `level` - The nesting level of the entry, starting at 1 for the least indented entries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TocEntry {
     pub title: String,
-    pub contents: Vec<String>,
+    pub page: usize,
+    pub level: u8,
 }
 
-impl Section {
-    /// Creates a vector of `Section` instances from a vector of `Page` instances.
-    ///
+/// The `CaptionKind` enum represents the kind of object a caption describes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CaptionKind {
+    Figure,
+    Table,
+}
+
+/// The `Caption` struct represents a figure or table caption.
+///
+/// # Fields
+///
+/// * `kind` - The kind of object the caption describes.
+/// * `label` - The label number of the object, e.g. "3" for "Figure 3".
+/// * `text` - The caption body without its label.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Caption {
+    pub kind: CaptionKind,
+    pub label: String,
+    pub text: String,
+}
+
+/// The `CaptionedFigure` struct represents a caption paired with the figure or table region it describes.
+///
+/// # Fields
+///
+/// * `caption` - The full text of the caption, e.g. "Figure 1: The Transformer - model architecture.".
+/// * `kind` - The kind of object the caption describes.
+/// * `label` - The label number of the object, e.g. "1" for "Figure 1".
+/// * `region` - The coordinates of the figure or table.
+/// * `page` - The page number of the caption and the region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptionedFigure {
+    pub caption: String,
+    pub kind: CaptionKind,
+    pub label: String,
+    pub region: Coordinate,
+    pub page: PageNumber,
+}
+
+/// Parses a caption such as "Figure 3: The architecture" or "Table 12. Results".
+///
+/// "Figure", "Fig.", "Table", and "Tab." are recognized case-insensitively, and the label
+/// must be followed by a colon or a period. See `CaptionPatterns` for other languages.
+///
+/// # Arguments
+///
+/// * `text` - The text of the caption block.
+///
+/// # Returns
+///
+/// An `Option` containing the kind, the label number, and the caption body, or `None` if the text is not a caption.
+pub fn parse_caption(text: &str) -> Option<(CaptionKind, String, String)> {
+    return CaptionPatterns::default().parse(text);
+}
+
+/// The `CaptionPatterns` struct holds the regular expressions that recognize captions.
+///
+/// Each pattern is paired with the kind of object it labels and must match the whole caption
+/// with a `label` group for the label number and a `text` group for the caption body.
+///
+/// # Fields
+///
+/// * `patterns` - The patterns with their caption kinds, tried in order.
+///
+/// # Examples
+///
+/// ```
+/// use rsrpp::parser::structs::{CaptionKind, CaptionPatterns};
+///
+/// let patterns = CaptionPatterns::with_german();
+/// let (kind, label, text) = patterns.parse("Abbildung 2: Das Modell").unwrap();
+/// assert_eq!(kind, CaptionKind::Figure);
+/// assert_eq!(label, "2");
+/// assert_eq!(text, "Das Modell");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CaptionPatterns {
+    pub patterns: Vec<(CaptionKind, regex::Regex)>,
+}
+
+impl CaptionPatterns {
+    /// Creates a `CaptionPatterns` instance from regular expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Pairs of caption kinds and regular expressions with `label` and `text` groups.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new `CaptionPatterns` instance, or an error if a pattern is invalid.
+    pub fn new(patterns: &[(CaptionKind, &str)]) -> Result<CaptionPatterns> {
+        let mut compiled = Vec::new();
+        for (kind, pattern) in patterns {
+            compiled.push((kind.clone(), regex::Regex::new(pattern)?));
+        }
+        return Ok(CaptionPatterns { patterns: compiled });
+    }
+
+    /// Returns the English patterns together with "Abbildung", "Abb.", and "Tabelle".
+    ///
+    /// # Returns
+    ///
+    /// A new `CaptionPatterns` instance.
+    pub fn with_german() -> CaptionPatterns {
+        let mut patterns = CaptionPatterns::default();
+        patterns.patterns.extend(
+            CaptionPatterns::new(&[
+                (
+                    CaptionKind::Figure,
+                    r"(?is)^(?:abbildung|abb\.)\s*(?P<label>\d+[a-z]?)\s*[:.]\s*(?P<text>.*)$",
+                ),
+                (
+                    CaptionKind::Table,
+                    r"(?is)^tabelle\s*(?P<label>\d+[a-z]?)\s*[:.]\s*(?P<text>.*)$",
+                ),
+            ])
+            .unwrap()
+            .patterns,
+        );
+        return patterns;
+    }
+
+    /// Returns the English patterns together with "図" and "表".
+    ///
+    /// Japanese captions often omit the colon, e.g. "図 2 モデルの構成", so the separator is optional.
+    ///
+    /// # Returns
+    ///
+    /// A new `CaptionPatterns` instance.
+    pub fn with_japanese() -> CaptionPatterns {
+        let mut patterns = CaptionPatterns::default();
+        patterns.patterns.extend(
+            CaptionPatterns::new(&[
+                (
+                    CaptionKind::Figure,
+                    r"(?s)^図\s*(?P<label>[0-9０-９]+)\s*[:：.．]?\s*(?P<text>.*)$",
+                ),
+                (
+                    CaptionKind::Table,
+                    r"(?s)^表\s*(?P<label>[0-9０-９]+)\s*[:：.．]?\s*(?P<text>.*)$",
+                ),
+            ])
+            .unwrap()
+            .patterns,
+        );
+        return patterns;
+    }
+
+    /// Parses a caption with the first matching pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the caption block.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the kind, the label number, and the caption body, or `None` if no pattern matches.
+    pub fn parse(&self, text: &str) -> Option<(CaptionKind, String, String)> {
+        let text = text.trim();
+        for (kind, pattern) in self.patterns.iter() {
+            if let Some(caps) = pattern.captures(text) {
+                let label = caps.name("label").map_or("", |label| label.as_str());
+                let body = caps.name("text").map_or("", |body| body.as_str());
+                return Some((kind.clone(), label.to_string(), body.trim().to_string()));
+            }
+        }
+        return None;
+    }
+}
+
+impl Default for CaptionPatterns {
+    fn default() -> CaptionPatterns {
+        return CaptionPatterns::new(&[
+            (
+                CaptionKind::Figure,
+                r"(?is)^(?:figure|fig\.)\s*(?P<label>\d+[a-z]?)\s*[:.]\s*(?P<text>.*)$",
+            ),
+            (
+                CaptionKind::Table,
+                r"(?is)^(?:table|tab\.)\s*(?P<label>\d+[a-z]?)\s*[:.]\s*(?P<text>.*)$",
+            ),
+        ])
+        .unwrap();
+    }
+}
+
+impl PartialEq for CaptionPatterns {
+    fn eq(&self, other: &CaptionPatterns) -> bool {
+        return self.patterns.len() == other.patterns.len()
+            && self
+                .patterns
+                .iter()
+                .zip(other.patterns.iter())
+                .all(|(a, b)| a.0 == b.0 && a.1.as_str() == b.1.as_str());
+    }
+}
+
+/// The `Script` enum represents the dominant writing system of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Script {
+    Latin,
+    Cjk,
+    Other,
+}
+
+/// The `Lang` enum represents the primary language of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Lang {
+    English,
+    Japanese,
+    Chinese,
+    Korean,
+    Unknown,
+}
+
+/// The `LanguageProfile` struct represents the language decision for a document.
+///
+/// # Fields
+///
+/// * `primary` - The primary language of the document.
+/// * `script` - The dominant script of the document.
+/// * `confidence` - The share of letters written in the dominant script, from 0.0 to 1.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageProfile {
+    pub primary: Lang,
+    pub script: Script,
+    pub confidence: f32,
+}
+
+/// The `NumericMention` struct represents a reported number in the text, e.g. a metric.
+///
+/// # Fields
+///
+/// * `value` - The numeric value.
+/// * `unit` - The unit following the value, e.g. "%" or "ms", if any.
+/// * `context` - The text surrounding the value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumericMention {
+    pub value: f64,
+    pub unit: Option<String>,
+    pub context: String,
+}
+
+/// The `CitationStyle` enum represents how a paper cites its references.
+///
+/// # Variants
+///
+/// * `Numeric` - Citations are numbers, e.g. "[12]".
+/// * `AuthorYear` - Citations are authors and years, e.g. "(Smith, 2023)".
+/// * `Unknown` - No citation marker was found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CitationStyle {
+    Numeric,
+    AuthorYear,
+    Unknown,
+}
+
+/// The `PaperMetadata` struct represents metadata of a paper extracted from its front matter.
+///
+/// # Fields
+///
+/// * `title` - The title of the paper, if known.
+/// * `authors` - The names of the authors.
+/// * `email_domains` - The unique domains of the author emails, e.g. "mit.edu".
+/// * `keywords` - The author-provided keywords, e.g. from a "Keywords:" line after the abstract.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaperMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub email_domains: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl PaperMetadata {
+    /// Creates a `PaperMetadata` instance from the front matter of a PDF document.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - A reference to a vector of `Page` instances.
+    ///
+    /// # Returns
+    ///
+    /// A `PaperMetadata` instance.
+    pub fn from_pages(pages: &Vec<Page>) -> PaperMetadata {
+        return PaperMetadata::from_pages_with_config(pages, &ParserConfig::new());
+    }
+
+    /// Creates a `PaperMetadata` instance from the front matter of a PDF document,
+    /// falling back to the `pdfinfo` metadata in `config.pdf_info`.
+    ///
+    /// The title is recovered from the first page first, since `pdfinfo` titles are often
+    /// stale or decorated, e.g. "Attention Is All You Need (v7)"; see `recover_title`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - A reference to a vector of `Page` instances.
+    /// * `config` - A reference to a `ParserConfig` instance containing the PDF information.
+    ///
+    /// # Returns
+    ///
+    /// A `PaperMetadata` instance.
+    pub fn from_pages_with_config(pages: &Vec<Page>, config: &ParserConfig) -> PaperMetadata {
+        let pdf_title = config
+            .pdf_info
+            .get("title")
+            .map(|title| title.trim().to_string())
+            .filter(|title| is_plausible_title(title));
+        return PaperMetadata {
+            title: recover_title(pages).or(pdf_title),
+            authors: extract_authors(pages),
+            email_domains: extract_email_domains(pages),
+            keywords: extract_keywords(pages),
+        };
+    }
+}
+
+/// Returns whether a metadata title looks like a real title rather than a file name.
+///
+/// Empty titles, titles without spaces such as "paper_final_v3", and file names such as
+/// "main.tex" are rejected.
+fn is_plausible_title(title: &str) -> bool {
+    let file_regex = regex::Regex::new(r"(?i)\.(pdf|tex|dvi|docx?)$").unwrap();
+    return title.contains(' ') && !file_regex.is_match(title);
+}
+
+/// Recovers the title of a paper from the first page.
+///
+/// The title is the block set in the largest font on the upper half of the first page.
+/// Vertical text such as the arXiv identifier in the margin is ignored.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// An `Option` containing the lines of the title block joined by spaces, or `None` if the first page has no text.
+pub fn recover_title(pages: &Vec<Page>) -> Option<String> {
+    let page = pages.iter().find(|page| page.page_nubmer == 1)?;
+    let block = title_block(page)?;
+    let lines = block.lines.iter().map(|line| line.get_text()).collect::<Vec<String>>();
+    let title = lines.join(" ").split_whitespace().collect::<Vec<&str>>().join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    return Some(title);
+}
+
+/// Finds the block set in the largest font on the upper half of a page; see `recover_title`.
+///
+/// # Arguments
+///
+/// * `page` - The first page of a PDF document.
+///
+/// # Returns
+///
+/// An `Option` containing the title block, or `None` if the upper half of the page has no text.
+fn title_block(page: &Page) -> Option<&Block> {
+    let mut best: Option<(f32, &Block)> = None;
+    for block in page.blocks.iter().filter(|block| block.y < page.height / 2.0) {
+        let font_size = block
+            .lines
+            .iter()
+            .filter(|line| line.width >= line.height)
+            .flat_map(|line| line.words.iter())
+            .map(|word| word.font_size())
+            .fold(0.0, f32::max);
+        if font_size <= 0.0 || block.get_text().to_lowercase().starts_with("arxiv:") {
+            continue;
+        }
+        let is_better = match best {
+            Some((best_size, best_block)) => {
+                font_size > best_size || (font_size == best_size && block.y < best_block.y)
+            }
+            None => true,
+        };
+        if is_better {
+            best = Some((font_size, block));
+        }
+    }
+    return best.map(|(_, block)| block);
+}
+
+/// Extracts the author names from the first page of a PDF document.
+///
+/// The blocks below the title (see `recover_title`) and above the abstract, or above the
+/// middle of the page if no "Abstract" heading is found, are read line by line. Each line is
+/// split on commas, "and", "&", and footnote markers, and the parts that look like names are
+/// kept: two to five words starting with an uppercase letter, without digits or
+/// "@", and without words such as "University" or "Research" that mark an affiliation or
+/// the organization names of the author emails, e.g. "Google" for "google.com".
+///
+/// # Arguments
+///
+/// * `pages` - The pages of a PDF document.
+///
+/// # Returns
+///
+/// A vector of unique author names in reading order.
+pub fn extract_authors(pages: &[Page]) -> Vec<String> {
+    let affiliation_words = [
+        "university",
+        "institute",
+        "institut",
+        "college",
+        "school",
+        "department",
+        "faculty",
+        "laboratory",
+        "laboratories",
+        "lab",
+        "labs",
+        "research",
+        "center",
+        "centre",
+        "inc",
+        "inc.",
+        "ltd",
+        "ltd.",
+        "corporation",
+        "corp.",
+        "company",
+        "gmbh",
+        "llc",
+    ];
+    // the names of the organizations in the author emails, e.g. "google" for "google.com"
+    // or "toronto" for "cs.toronto.edu", also mark an affiliation such as "Google Brain"
+    let generic_labels = ["ac", "co", "com", "edu", "gov", "net", "org"];
+    let email_labels = extract_email_domains(pages)
+        .iter()
+        .flat_map(|domain| {
+            let labels = domain.split('.').collect::<Vec<&str>>();
+            labels[..labels.len() - 1].iter().map(|label| label.to_string()).collect::<Vec<_>>()
+        })
+        .filter(|label| !generic_labels.contains(&label.as_str()))
+        .collect::<HashSet<String>>();
+    let particles = [
+        "de", "van", "von", "der", "den", "la", "le", "di", "da", "del",
+    ];
+    // footnote markers such as "Vaswani∗" or "Vaswani1" also separate names on a shared line
+    let separator_regex = regex::Regex::new(r",|;|&|\band\b|[*∗†‡§¶#\d]+").unwrap();
+
+    let page = match pages.iter().find(|page| page.page_nubmer == 1) {
+        Some(page) => page,
+        None => return Vec::new(),
+    };
+    let title = match title_block(page) {
+        Some(block) => block,
+        None => return Vec::new(),
+    };
+    let title_bottom = title.y + title.height;
+    let abstract_top = page
+        .blocks
+        .iter()
+        .filter(|block| block.y >= title_bottom)
+        .filter(|block| block.get_text().trim().to_lowercase().starts_with("abstract"))
+        .map(|block| block.y)
+        .fold(page.height / 2.0, f32::min);
+
+    let mut blocks = page
+        .blocks
+        .iter()
+        .filter(|block| block.y >= title_bottom && block.y < abstract_top)
+        .collect::<Vec<&Block>>();
+    blocks.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap().then(a.x.partial_cmp(&b.x).unwrap()));
+
+    let mut authors: Vec<String> = Vec::new();
+    for line in blocks.iter().flat_map(|block| block.lines.iter()) {
+        let text = line.get_text();
+        if text.contains('@') {
+            continue;
+        }
+        for part in separator_regex.split(&text) {
+            let words = part.split_whitespace().collect::<Vec<&str>>();
+            if words.len() < 2 || words.len() > 5 {
+                continue;
+            }
+            let is_name = words.iter().enumerate().all(|(i, word)| {
+                let starts_upper = word.chars().next().map_or(false, |c| c.is_uppercase());
+                starts_upper || (i > 0 && particles.contains(word))
+            }) && !words.iter().any(|word| {
+                let word = word.to_lowercase();
+                affiliation_words.contains(&word.as_str()) || email_labels.contains(&word)
+            });
+            let name = words.join(" ");
+            if is_name && !authors.contains(&name) {
+                authors.push(name);
+            }
+        }
+    }
+    return authors;
+}
+
+/// The `Paper` struct represents a parsed paper with its metadata, sections, references, and pages.
+///
+/// # Fields
+///
+/// * `metadata` - The metadata of the paper.
+/// * `sections` - The sections of the paper in document order.
+/// * `references` - The references of the References section, see `parse_references_heuristic`.
+/// * `pages` - The parsed pages, including the detected tables.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Paper {
+    pub metadata: PaperMetadata,
+    pub sections: Vec<Section>,
+    pub references: Vec<Reference>,
+    pub pages: Vec<Page>,
+}
+
+impl Paper {
+    /// Creates a `Paper` instance from a vector of `Page` instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - A vector of `Page` instances.
+    /// * `config` - A reference to a `ParserConfig` instance containing the section options.
+    ///
+    /// # Returns
+    ///
+    /// A `Paper` instance.
+    pub fn from_pages(pages: Vec<Page>, config: &ParserConfig) -> Paper {
+        let sections = Section::from_pages_with_config(&pages, config);
+        // each entry is parsed on its own to keep the split of `Section::references_raw`
+        let references = Section::references_raw(&sections)
+            .iter()
+            .flat_map(|entry| super::parse_references_heuristic(entry))
+            .collect::<Vec<Reference>>();
+        return Paper {
+            metadata: PaperMetadata::from_pages_with_config(&pages, config),
+            sections: sections,
+            references: references,
+            pages: pages,
+        };
+    }
+
+    /// Returns the text of the abstract. `abstract` is a reserved word in Rust.
+    ///
+    /// See `Section::abstract_text`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the text of the Abstract section, or `None` if there is none.
+    pub fn abstract_text(&self) -> Option<String> {
+        return Section::abstract_text(&self.sections);
+    }
+
+    /// Returns the section with the given title, compared with `Section::matches_title`.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the section, e.g. "Introduction".
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the first matching section.
+    pub fn section(&self, title: &str) -> Option<&Section> {
+        return self.sections.iter().find(|section| section.matches_title(title));
+    }
+
+    /// Returns the text of the main body, from the introduction up to the back matter.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the text of the body sections, separated by blank lines.
+    pub fn body_text(&self) -> String {
+        return super::partition_document(&self.sections)
+            .body
+            .iter()
+            .map(|section| section.get_text())
+            .collect::<Vec<String>>()
+            .join("\n\n");
+    }
+
+    /// Returns the references of the References section.
+    pub fn references(&self) -> &Vec<Reference> {
+        return &self.references;
+    }
+
+    /// Returns the tables detected on every page.
+    ///
+    /// # Returns
+    ///
+    /// A vector of page numbers and detected tables in document order.
+    pub fn tables(&self) -> Vec<(PageNumber, DetectedTable)> {
+        return self
+            .pages
+            .iter()
+            .flat_map(|page| page.tables.iter().map(|table| (page.page_nubmer, table.clone())))
+            .collect();
+    }
+}
+
+/// Extracts the unique domains of the author emails on the first page of a PDF document.
+///
+/// The brace notation "{a,b,c}@inst.edu" for several authors sharing a domain is supported.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A vector of lowercase domains in order of appearance.
+pub fn extract_email_domains(pages: &[Page]) -> Vec<String> {
+    let email_regex =
+        regex::Regex::new(r"(\{[^}]*\}|[A-Za-z0-9._%+-]+)\s*@\s*([A-Za-z0-9-]+(\.[A-Za-z0-9-]+)+)")
+            .unwrap();
+    let mut domains: Vec<String> = Vec::new();
+    for page in pages.iter().filter(|page| page.page_nubmer == 1) {
+        let text = page.get_text();
+        for caps in email_regex.captures_iter(&text) {
+            let domain = caps[2].to_lowercase();
+            if !domains.contains(&domain) {
+                domains.push(domain);
+            }
+        }
+    }
+    return domains;
+}
+
+/// Extracts the author-provided keywords from the first two pages of a PDF document.
+///
+/// Only the blocks of the Abstract are searched, as in `Section::from_pages_with_config`,
+/// so body text starting with e.g. "Keywords" is not mistaken for a keywords line.
+///
+/// # Arguments
+///
+/// * `pages` - A reference to a vector of `Page` instances.
+///
+/// # Returns
+///
+/// A vector of keywords from the first keywords line, or an empty vector if there is none.
+pub fn extract_keywords(pages: &Vec<Page>) -> Vec<String> {
+    return pages
+        .iter()
+        .filter(|page| page.page_nubmer <= 2)
+        .flat_map(|page| page.blocks.iter())
+        .filter(|block| Section::normalize_title(&block.section) == "abstract")
+        .find_map(|block| block.split_keywords())
+        .map(|(_, keywords)| keywords)
+        .unwrap_or_default();
+}
+
+/// The `DocumentParts` struct represents a coarse split of the sections of a PDF document.
+///
+/// # Fields
+///
+/// * `front` - The front matter, e.g. the abstract.
+/// * `body` - The main text, e.g. from the introduction to the conclusion.
+/// * `back` - The back matter, e.g. acknowledgments, references, and appendices.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentParts {
+    pub front: Vec<Section>,
+    pub body: Vec<Section>,
+    pub back: Vec<Section>,
+}
+
+/// The `Section` struct represents a section in a PDF document.
+///
+/// # Fields
+///
+/// * `title` - The title of the section.
+/// * `content` - The content of the section.
+/// * `raw_title` - The title as detected in the document when it was replaced via `ParserConfig::section_aliases`.
+/// * `content_types` - The `BlockType` of each entry in `contents`; empty unless requested via `ParserConfig::include_block_types`.
+/// * `captions` - The figure and table captions of the section, which are not part of `contents`.
+/// * `footnotes` - The footnotes of the section, which are not part of `contents`.
+/// * `number` - The section number of the heading, e.g. "3.1", if the heading is numbered.
+/// * `elements` - The paragraphs and captions of the section in reading order; empty unless requested via `ParserConfig::include_elements`.
+/// * `level` - The depth of the section, e.g. 1 for "3 Model Architecture" and 2 for "3.1 Encoder and Decoder Stacks"; unnumbered sections are level 1.
+/// * `parent` - The title of the enclosing section, e.g. "Model Architecture" for "3.1", if it was found.
+/// * `tables` - The tables of the section with their cell texts, placed under the nearest block above each table.
+/// * `blocks` - The headers, paragraphs, and captions of the section with their `BlockType` in reading order; empty unless requested via `ParserConfig::include_block_types`.
+/// * `spans` - The source page and bounding box of each entry in `contents`; empty unless requested via `ParserConfig::track_provenance`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Section {
+    pub index: usize,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_title: Option<String>,
+    pub contents: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_types: Vec<BlockType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Caption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub footnotes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<SectionElement>,
+    #[serde(default)]
+    pub level: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<Table>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ClassifiedBlock>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<TextSpan>,
+}
+
+impl Section {
+    /// Creates a vector of `Section` instances from a vector of `Page` instances.
+    ///
     /// # Arguments
     ///
     /// * `pages` - A reference to a vector of `Page` instances.
@@ -667,20 +2687,147 @@ impl Section {
     ///
     /// A vector of `Section` instances, each representing a section in the PDF document.
     pub fn from_pages(pages: &Vec<Page>) -> Vec<Section> {
-        let mut section_indices: HashMap<String, i8> = HashMap::new();
+        return Section::from_pages_with_config(pages, &ParserConfig::new());
+    }
+
+    /// Creates a vector of `Section` instances from a vector of `Page` instances,
+    /// applying the section options of a `ParserConfig`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - A reference to a vector of `Page` instances.
+    /// * `config` - A reference to a `ParserConfig` instance containing the section options.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `Section` instances, each representing a section in the PDF document.
+    pub fn from_pages_with_config(pages: &Vec<Page>, config: &ParserConfig) -> Vec<Section> {
+        let mut section_indices: HashMap<String, usize> = HashMap::new();
         let mut section_map: HashMap<String, Vec<String>> = HashMap::new();
+        let mut section_types: HashMap<String, Vec<BlockType>> = HashMap::new();
+        let mut raw_titles: HashMap<String, String> = HashMap::new();
+        let mut section_captions: HashMap<String, Vec<Caption>> = HashMap::new();
+        let mut section_footnotes: HashMap<String, Vec<String>> = HashMap::new();
+        let mut section_numbers: HashMap<String, String> = HashMap::new();
+        let mut section_elements: HashMap<String, Vec<SectionElement>> = HashMap::new();
+        let mut section_blocks: HashMap<String, Vec<ClassifiedBlock>> = HashMap::new();
+        let mut section_spans: HashMap<String, Vec<TextSpan>> = HashMap::new();
         let mut last_text = String::new();
+        let mut last_sources: Vec<(PageNumber, Coordinate)> = Vec::new();
+        let number_ptn = regex::Regex::new(r"^(\d+(\.\d+)*)\.?\s+\S").unwrap();
+        let number_only_ptn = regex::Regex::new(r"^(\d+(\.\d+)*)\.?$").unwrap();
+        // a number set on its own line or block, e.g. "3" above "Method"
+        let mut pending_number: Option<String> = None;
         let eos_ptn = regex::Regex::new(r"(\.)(\W)").unwrap();
         let ex_ws_ptn = regex::Regex::new(r"\s+").unwrap();
+        let is_cjk =
+            config.language.as_ref().map_or(false, |language| language.script == Script::Cjk);
         for page in pages {
             for block in &page.blocks {
+                if let Some(line) = block.lines.first() {
+                    let first = line.get_text().trim().to_string();
+                    let previous_number = pending_number.take();
+                    let numbered_heading = if let Some(caps) = number_only_ptn.captures(&first) {
+                        match block.lines.get(1) {
+                            Some(next) => Some((caps[1].to_string(), next.get_text())),
+                            None => {
+                                pending_number = Some(caps[1].to_string());
+                                None
+                            }
+                        }
+                    } else if let Some(caps) = number_ptn.captures(&first) {
+                        Some((caps[1].to_string(), first.clone()))
+                    } else {
+                        previous_number.map(|number| (number, first.clone()))
+                    };
+                    if let Some((number, heading)) = numbered_heading {
+                        if super::normalize_heading(&heading)
+                            == super::normalize_heading(&block.section)
+                        {
+                            let title =
+                                Section::resolve_alias(&block.section, &config.section_aliases);
+                            section_numbers.entry(title).or_insert(number);
+                        }
+                    }
+                }
+
+                // a keywords line after the abstract is metadata, not part of the abstract
+                let keywords_split = if Section::normalize_title(&block.section) == "abstract" {
+                    block.split_keywords()
+                } else {
+                    None
+                };
+                let block = match &keywords_split {
+                    Some((prose, _)) => prose,
+                    None => block,
+                };
+                if block.lines.is_empty() {
+                    continue;
+                }
+
                 let keys = section_map.keys().cloned().collect::<Vec<String>>();
-                let mut text_block = block.get_text().trim().to_string();
+                let block_text = |block: &Block| -> String {
+                    if is_cjk {
+                        let text =
+                            block.lines.iter().map(|line| line.get_text()).collect::<String>();
+                        return text.trim().to_string();
+                    } else if config.dictionary_hyphenation {
+                        return block.get_text_with_dictionary().trim().to_string();
+                    }
+                    return block.get_text().trim().to_string();
+                };
+                let mut text_block = block_text(block);
+
+                if block.block_type == BlockType::Caption {
+                    if let Some((kind, label, text)) = config.caption_patterns.parse(&text_block) {
+                        let title = Section::resolve_alias(&block.section, &config.section_aliases);
+                        section_captions.entry(title.clone()).or_insert_with(Vec::new).push(
+                            Caption {
+                                kind: kind,
+                                label: label,
+                                text: text,
+                            },
+                        );
+                        section_blocks.entry(title.clone()).or_insert_with(Vec::new).push(
+                            ClassifiedBlock {
+                                text: text_block.clone(),
+                                block_type: BlockType::Caption,
+                            },
+                        );
+                        section_elements
+                            .entry(title)
+                            .or_insert_with(Vec::new)
+                            .push(SectionElement::Caption(text_block));
+                        continue;
+                    }
+                }
+
+                if block.block_type == BlockType::Footnote {
+                    let title = Section::resolve_alias(&block.section, &config.section_aliases);
+                    // one entry per footnote, even when they share a block
+                    section_footnotes.entry(title.clone()).or_insert_with(Vec::new).extend(
+                        block.split_footnotes().iter().map(|footnote| {
+                            ex_ws_ptn.replace_all(&block_text(footnote), " ").to_string()
+                        }),
+                    );
+                    section_blocks.entry(title).or_insert_with(Vec::new).push(ClassifiedBlock {
+                        text: text_block,
+                        block_type: BlockType::Footnote,
+                    });
+                    continue;
+                }
 
-                if text_block.ends_with("-") {
+                let source = (
+                    page.page_nubmer,
+                    Coordinate::from_object(block.x, block.y, block.width, block.height),
+                );
+                if !is_cjk && text_block.ends_with("-") {
                     last_text.push_str(&text_block.trim_end_matches("-"));
+                    last_sources.push(source);
                     continue;
                 }
+                let mut sources = std::mem::take(&mut last_sources);
+                sources.push(source);
 
                 if !last_text.is_empty() {
                     last_text.push_str(&text_block);
@@ -688,30 +2835,290 @@ impl Section {
                     last_text.clear();
                 }
 
-                text_block = eos_ptn.replace_all(&text_block, "$1 $2").to_string();
+                if !is_cjk {
+                    text_block = eos_ptn.replace_all(&text_block, "$1 $2").to_string();
+                }
                 text_block = ex_ws_ptn.replace_all(&text_block, " ").to_string();
+                if config.normalize_punctuation {
+                    text_block = normalize_punctuation_ascii(&text_block);
+                }
 
-                if keys.contains(&block.section) {
-                    let content = section_map.get_mut(&block.section).unwrap();
-                    content.push(text_block);
+                let title = Section::resolve_alias(&block.section, &config.section_aliases);
+                let start = section_map
+                    .get(&title)
+                    .map_or(0, |content| content.iter().map(|text| text.len() + 1).sum());
+                if keys.contains(&title) {
+                    let content = section_map.get_mut(&title).unwrap();
+                    // overlapping blocks and repeated headers can yield the same paragraph twice
+                    if content.last().map(|last| last.trim()) == Some(text_block.trim()) {
+                        continue;
+                    }
+                    content.push(text_block.clone());
+                    let types = section_types.get_mut(&title).unwrap();
+                    types.push(block.block_type.clone());
                 } else {
-                    section_map.insert(block.section.clone(), vec![text_block]);
-                    section_indices.insert(block.section.clone(), section_indices.len() as i8);
+                    section_map.insert(title.clone(), vec![text_block.clone()]);
+                    section_types.insert(title.clone(), vec![block.block_type.clone()]);
+                    section_indices.insert(title.clone(), section_indices.len());
+                }
+                let spans = section_spans.entry(title.clone()).or_insert_with(Vec::new);
+                for (page_number, coord) in sources {
+                    spans.push(TextSpan {
+                        page: page_number,
+                        coord: coord,
+                        text_range: (start, start + text_block.len()),
+                    });
+                }
+                section_blocks.entry(title.clone()).or_insert_with(Vec::new).push(
+                    ClassifiedBlock {
+                        text: text_block.clone(),
+                        block_type: block.block_type.clone(),
+                    },
+                );
+                section_elements
+                    .entry(title.clone())
+                    .or_insert_with(Vec::new)
+                    .push(SectionElement::Paragraph(text_block));
+                if title != block.section && !raw_titles.contains_key(&title) {
+                    raw_titles.insert(title, block.section.clone());
+                }
+            }
+        }
+        // a table belongs to the section of the nearest block above it on its page
+        let mut section_tables: HashMap<String, Vec<Table>> = HashMap::new();
+        for page in pages {
+            for table in page.tables.iter().filter(|table| !table.rows.is_empty()) {
+                let block = page
+                    .blocks
+                    .iter()
+                    .filter(|block| block.y <= table.coord.top_left.y)
+                    .max_by(|a, b| a.y.partial_cmp(&b.y).unwrap())
+                    .or(page.blocks.first());
+                if let Some(block) = block {
+                    let title = Section::resolve_alias(&block.section, &config.section_aliases);
+                    section_tables.entry(title).or_insert_with(Vec::new).push(table.to_table());
                 }
             }
         }
+
         let mut sections = Vec::new();
         for (title, contents) in section_map {
+            let (content_types, blocks) = if config.include_block_types {
+                (
+                    section_types.remove(&title).unwrap(),
+                    section_blocks.remove(&title).unwrap_or_default(),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            let spans = if config.track_provenance {
+                section_spans.remove(&title).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            let elements = if config.include_elements {
+                section_elements.remove(&title).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
             sections.push(Section {
                 index: section_indices.get(&title).unwrap().clone(),
+                raw_title: raw_titles.remove(&title),
+                number: section_numbers.remove(&title),
+                captions: section_captions.remove(&title).unwrap_or_default(),
+                footnotes: section_footnotes.remove(&title).unwrap_or_default(),
+                elements: elements,
+                tables: section_tables.remove(&title).unwrap_or_default(),
+                blocks: blocks,
+                spans: spans,
                 title: title,
                 contents: contents,
+                content_types: content_types,
+                level: 1,
+                parent: None,
             });
         }
+        sections.retain(|section| {
+            !config.drop_sections.iter().any(|title| section.matches_title(title))
+        });
         sections.sort_by(|a, b| a.index.cmp(&b.index));
+
+        // numbered sections are ordered by their numbers within the slots they occupy,
+        // so a title discovered early (e.g. a forward reference) cannot jump ahead
+        let slots = sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| section.number.is_some())
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        let mut numbered = slots.iter().map(|i| sections[*i].clone()).collect::<Vec<Section>>();
+        numbered.sort_by_key(|section| Section::parse_number(section.number.as_ref().unwrap()));
+        for (slot, section) in slots.into_iter().zip(numbered) {
+            sections[slot] = section;
+        }
+        for (i, section) in sections.iter_mut().enumerate() {
+            section.index = i;
+        }
+
+        // the depth of the section number gives the level, e.g. 2 for "3.1" under "3"
+        let numbered = sections
+            .iter()
+            .filter_map(|section| {
+                section.number.clone().map(|number| (number, section.title.clone()))
+            })
+            .collect::<Vec<(String, String)>>();
+        for section in sections.iter_mut() {
+            if let Some(number) = &section.number {
+                section.level = Section::parse_number(number).len().max(1) as u8;
+                if let Some((parent_number, _)) = number.rsplit_once('.') {
+                    section.parent = numbered
+                        .iter()
+                        .find(|(number, _)| number == parent_number)
+                        .map(|(_, title)| title.clone());
+                }
+            }
+        }
         return sections;
     }
 
+    /// Parses a section number such as "3.1" into its numeric parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The section number.
+    ///
+    /// # Returns
+    ///
+    /// A vector containing the numeric parts of the section number.
+    fn parse_number(number: &str) -> Vec<u32> {
+        return number.split('.').filter_map(|part| part.parse::<u32>().ok()).collect();
+    }
+
+    /// Returns whether the `Section` is the abstract.
+    pub fn is_abstract(&self) -> bool {
+        return Section::normalize_title(&self.title) == "abstract";
+    }
+
+    /// Returns the text of the abstract among the sections.
+    ///
+    /// Blocks before the first detected heading are assigned to the Abstract section, so the
+    /// text is found even when the paper has no explicit "Abstract" heading.
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - The sections of a paper.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the trimmed contents of the section titled "Abstract", compared
+    /// case-insensitively, or `None` if there is no such section or it is empty.
+    pub fn abstract_text(sections: &[Section]) -> Option<String> {
+        let text = sections.iter().find(|section| section.is_abstract())?.get_text();
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        return Some(text.to_string());
+    }
+
+    /// Returns the entries of the References or Bibliography section as raw text.
+    ///
+    /// Numbered references such as "[1] ..." are split at their markers across the whole
+    /// section. Otherwise each paragraph starts a new entry, as with hanging indents, unless
+    /// it continues the previous one: when the previous paragraph does not end with a period
+    /// or the paragraph starts with a lowercase letter, e.g. a reference broken across two
+    /// columns. Paragraphs holding several author-year entries are split further with
+    /// `split_reference_text`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - The sections of a paper.
+    ///
+    /// # Returns
+    ///
+    /// A vector containing the text of each reference, or an empty vector if there is no References section.
+    pub fn references_raw(sections: &[Section]) -> Vec<String> {
+        let section = match sections.iter().find(|section| section.is_references()) {
+            Some(section) => section,
+            None => return Vec::new(),
+        };
+        let contents = section
+            .contents
+            .iter()
+            .map(|content| content.trim())
+            .filter(|content| !content.is_empty())
+            .collect::<Vec<&str>>();
+        let starts_with_marker =
+            |content: &&str| REFERENCE_MARKER_REGEX.find(content).map_or(false, |m| m.start() == 0);
+        if contents.iter().any(starts_with_marker) {
+            return super::split_reference_text(&contents.join(" "));
+        }
+
+        let mut paragraphs: Vec<String> = Vec::new();
+        for content in contents {
+            let continues = match paragraphs.last() {
+                Some(last) => {
+                    !last.ends_with('.')
+                        || content.chars().next().map_or(false, |c| c.is_lowercase())
+                }
+                None => false,
+            };
+            if continues {
+                let last = paragraphs.last_mut().unwrap();
+                last.push(' ');
+                last.push_str(content);
+            } else {
+                paragraphs.push(content.to_string());
+            }
+        }
+        return paragraphs
+            .iter()
+            .flat_map(|paragraph| super::split_reference_text(paragraph))
+            .collect();
+    }
+
+    /// Returns whether the `Section` is the list of references.
+    pub fn is_references(&self) -> bool {
+        return Section::normalize_title(&self.title) == "references";
+    }
+
+    /// Returns whether the `Section` is an appendix or supplementary material.
+    pub fn is_appendix(&self) -> bool {
+        let title = Section::normalize_title(&self.title);
+        return title == "appendix"
+            || title.starts_with("appendix ")
+            || title.starts_with("supplementary material");
+    }
+
+    /// Returns whether the `Section` is the acknowledgments.
+    pub fn is_acknowledgments(&self) -> bool {
+        return Section::normalize_title(&self.title) == "acknowledgments";
+    }
+
+    /// Resolves a section title to its canonical title using a map of aliases.
+    ///
+    /// Alias keys are compared with the title after normalization with `Section::normalize_title`.
+    /// When several keys normalize to the same title, e.g. "Prior Work" and "prior work", the
+    /// first key in the order of the map wins, so the result does not depend on hashing.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The detected section title.
+    /// * `aliases` - A map of section titles to canonical titles.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the canonical title, or `title` itself if no alias matches.
+    pub fn resolve_alias(title: &str, aliases: &BTreeMap<String, String>) -> String {
+        let normalized = Section::normalize_title(title);
+        for (alias, canonical) in aliases.iter() {
+            if Section::normalize_title(alias) == normalized {
+                return canonical.clone();
+            }
+        }
+        return title.to_string();
+    }
+
     /// Returns the concatenated text of all `TextBlock` instances in the `Section`.
     ///
     /// # Returns
@@ -724,4 +3131,203 @@ impl Section {
             return self.contents.join("\n");
         }
     }
+
+    /// Renders the `Section` as Markdown.
+    ///
+    /// The title becomes a heading whose depth is the `level` of the section, contents become
+    /// paragraphs, captions become italicized lines, and `<math>` tags become `$...$`. When
+    /// `elements` is populated, paragraphs and captions are rendered in reading order;
+    /// otherwise the captions follow the paragraphs.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the Markdown of the section.
+    pub fn to_markdown(&self) -> String {
+        let math_regex = regex::Regex::new(r"(?s)<math[^>]*>(.*?)</math>").unwrap();
+        let math = |text: &str| math_regex.replace_all(text, "$$${1}$$").to_string();
+        let caption = |text: &str| format!("*{}*", math(text.trim()));
+
+        let mut blocks = vec![format!(
+            "{} {}",
+            "#".repeat(self.level.max(1) as usize),
+            self.title
+        )];
+        if self.elements.is_empty() {
+            blocks.extend(self.contents.iter().map(|content| math(content.trim())));
+            blocks.extend(self.captions.iter().map(|c| {
+                let kind = match c.kind {
+                    CaptionKind::Figure => "Figure",
+                    CaptionKind::Table => "Table",
+                };
+                caption(&format!("{} {}: {}", kind, c.label, c.text))
+            }));
+        } else {
+            blocks.extend(self.elements.iter().map(|element| match element {
+                SectionElement::Paragraph(text) => math(text.trim()),
+                SectionElement::Caption(text) => caption(text),
+                SectionElement::Equation(text) => format!("$${}$$", text.trim()),
+            }));
+        }
+        return blocks.join("\n\n") + "\n";
+    }
+
+    /// Returns whether the section text mentions `needle`, ignoring case, punctuation, and spacing.
+    ///
+    /// Both sides are normalized with `normalize_for_matching` and compared with all spaces
+    /// removed, so "data-driven" matches "Data driven" and "datadriven".
+    ///
+    /// # Arguments
+    ///
+    /// * `needle` - The term to search for.
+    ///
+    /// # Returns
+    ///
+    /// A `bool` indicating whether the section mentions the term.
+    pub fn contains(&self, needle: &str) -> bool {
+        let needle = normalize_for_matching(needle).replace(' ', "");
+        let text = normalize_for_matching(&self.get_text()).replace(' ', "");
+        return text.contains(&needle);
+    }
+
+    /// Returns the first `max_words` words of the section text as a preview.
+    ///
+    /// A `<math>...</math>` span counts as a single word and is never cut. When the text is
+    /// longer than `max_words` words, "..." is appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_words` - The maximum number of words in the preview.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the preview, with words separated by single spaces.
+    pub fn preview(&self, max_words: usize) -> String {
+        let text = self.get_text();
+        let word_regex = regex::Regex::new(r"(?s)\S*<math\b[^>]*>.*?</math>\S*|\S+").unwrap();
+        let words = word_regex.find_iter(&text).map(|m| m.as_str()).collect::<Vec<&str>>();
+        if words.len() <= max_words {
+            return words.join(" ");
+        }
+        return format!("{}...", words[..max_words].join(" "));
+    }
+
+    /// Normalizes a section title for matching.
+    ///
+    /// The title is lowercased, a leading section number ("3", "2.1.") is removed,
+    /// whitespace is collapsed, and trailing punctuation is trimmed. Titles listed in
+    /// `SECTION_SYNONYMS` are mapped to the first entry of their group, so "Conclusions"
+    /// and "Concluding Remarks" both normalize to "conclusion".
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The section title to normalize.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the normalized title.
+    pub fn normalize_title(title: &str) -> String {
+        let title = strip_section_number(&title.to_lowercase());
+        let title = title.split_whitespace().collect::<Vec<&str>>().join(" ");
+        let title = title.trim_end_matches(|c: char| c == ':' || c == '.').trim().to_string();
+        for synonyms in SECTION_SYNONYMS {
+            if synonyms.contains(&title.as_str()) {
+                return synonyms[0].to_string();
+            }
+        }
+        return title;
+    }
+
+    /// Returns whether the title of the `Section` matches the given title after normalization.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title to compare with, normalized by `Section::normalize_title`.
+    ///
+    /// # Returns
+    ///
+    /// A `bool` indicating whether the titles match.
+    pub fn matches_title(&self, title: &str) -> bool {
+        return Section::normalize_title(&self.title) == Section::normalize_title(title);
+    }
+}
+
+/// The `SectionTree` struct represents a section with its subsections.
+///
+/// # Fields
+///
+/// * `section` - The section.
+/// * `children` - The subsections of the section in document order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectionTree {
+    pub section: Section,
+    pub children: Vec<SectionTree>,
+}
+
+impl SectionTree {
+    /// Nests a flat list of sections by their `level`.
+    ///
+    /// Each section becomes a child of the nearest preceding section with a lower level.
+    ///
+    /// # Arguments
+    ///
+    /// * `sections` - The sections of the document in document order, e.g. from `Section::from_pages`.
+    ///
+    /// # Returns
+    ///
+    /// A vector of the top-level `SectionTree` instances.
+    pub fn from_sections(sections: &[Section]) -> Vec<SectionTree> {
+        let mut roots: Vec<SectionTree> = Vec::new();
+        // the path of open sections from the root to the last inserted section
+        let mut path: Vec<SectionTree> = Vec::new();
+        for section in sections.iter() {
+            while path.last().map_or(false, |open| open.section.level >= section.level) {
+                SectionTree::close(&mut path, &mut roots);
+            }
+            path.push(SectionTree {
+                section: section.clone(),
+                children: Vec::new(),
+            });
+        }
+        while !path.is_empty() {
+            SectionTree::close(&mut path, &mut roots);
+        }
+        return roots;
+    }
+
+    /// Pops the last open section and attaches it to its parent or to the roots.
+    fn close(path: &mut Vec<SectionTree>, roots: &mut Vec<SectionTree>) {
+        let tree = path.pop().unwrap();
+        match path.last_mut() {
+            Some(parent) => parent.children.push(tree),
+            None => roots.push(tree),
+        }
+    }
 }
+
+/// Groups of section titles treated as synonyms by `Section::normalize_title`.
+/// The first entry of each group is the normalized form.
+const SECTION_SYNONYMS: &[&[&str]] = &[
+    &["introduction", "intro"],
+    &[
+        "related work",
+        "related works",
+        "prior work",
+        "background and related work",
+    ],
+    &[
+        "conclusion",
+        "conclusions",
+        "concluding remarks",
+        "conclusion and future work",
+        "conclusions and future work",
+    ],
+    &["limitations", "limitation", "limitations and future work"],
+    &["references", "bibliography"],
+    &[
+        "acknowledgments",
+        "acknowledgements",
+        "acknowledgment",
+        "acknowledgement",
+    ],
+    &["appendix", "appendices"],
+];
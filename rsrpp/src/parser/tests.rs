@@ -1,4 +1,7 @@
 use super::*;
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 #[tokio::test]
 async fn test_invalid_pdf_url() {
@@ -17,6 +20,141 @@ async fn test_invalid_pdf_url() {
     }
 }
 
+#[tokio::test]
+async fn test_download_pdf_missing_file() {
+    let config = ParserConfig::new();
+    let res = download_pdf("/tmp/rsrpp-missing.pdf", &config).await;
+    assert!(matches!(res, Err(ParseError::Io(_))));
+    config.clean_files().unwrap();
+}
+
+#[tokio::test]
+async fn test_download_pdf_http_error() {
+    let config = ParserConfig::new();
+    let res = download_pdf("https://arxiv.org/pdf/0000.00000", &config).await;
+    assert!(matches!(res, Err(ParseError::Download(_))));
+    assert!(!Path::new(&config.pdf_path).exists());
+    config.clean_files().unwrap();
+}
+
+#[tokio::test]
+async fn test_parse_error_invalid_pdf() {
+    let mut config = ParserConfig::new();
+    let path = "/tmp/rsrpp-not-a-pdf.pdf";
+    std::fs::write(path, "not a pdf").unwrap();
+
+    let res = parse(path, &mut config, false).await;
+    assert!(matches!(res, Err(ParseError::InvalidPdf)));
+
+    let _ = std::fs::remove_file(path);
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_check_dependencies_reports_missing_commands() {
+    let empty_dir = std::env::temp_dir().join("rsrpp-empty-path");
+    std::fs::create_dir_all(&empty_dir).unwrap();
+
+    // the search path is passed to the commands instead of changing PATH for the whole test process
+    let res = ParserConfig::check_dependencies_in(empty_dir.as_os_str());
+    assert_eq!(
+        res,
+        Err(vec![
+            "pdfinfo".to_string(),
+            "pdftocairo".to_string(),
+            "pdftohtml".to_string(),
+            "pdftotext".to_string(),
+        ])
+    );
+
+    let _ = std::fs::remove_dir(&empty_dir);
+}
+
+#[test]
+fn test_parse_error_conversions() {
+    let e = command_error(
+        "pdfinfo",
+        std::io::Error::from(std::io::ErrorKind::NotFound),
+    );
+    assert!(matches!(e, ParseError::MissingCommand(ref command) if command == "pdfinfo"));
+    assert_eq!(
+        e.to_string(),
+        "Error: command not found: pdfinfo; install poppler-utils"
+    );
+    let e = command_error(
+        "pdfinfo",
+        std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+    );
+    assert!(matches!(e, ParseError::Io(_)));
+
+    // errors raised inside anyhow-based helpers keep their variant
+    let e: anyhow::Error = ParseError::XmlTimeout.into();
+    assert!(matches!(ParseError::from(e), ParseError::XmlTimeout));
+    let e = ParseError::from(Error::msg("unexpected"));
+    assert!(matches!(e, ParseError::Other(_)));
+    assert_eq!(
+        ParseError::InvalidPdf.to_string(),
+        "Error: pdf file is broken or invalid url"
+    );
+}
+
+#[test]
+fn test_parse_blocking() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/parser/testdata/sample.pdf"
+    );
+    let mut config = ParserConfig::new();
+    let pages = parse_blocking(path, &mut config, false).unwrap();
+    let _ = config.clean_files();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut config = ParserConfig::new();
+    let expected = runtime.block_on(parse(path, &mut config, false)).unwrap();
+    let _ = config.clean_files();
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages, expected);
+}
+
+#[tokio::test]
+async fn test_parse_blocking_inside_runtime() {
+    let mut config = ParserConfig::new();
+    let res = parse_blocking("/tmp/rsrpp-not-a-pdf.pdf", &mut config, false);
+    let err = res.unwrap_err();
+    assert!(matches!(err, ParseError::InsideRuntime));
+    assert_eq!(
+        err.to_string(),
+        "Error: parse_blocking cannot be called from within an async runtime, use parse instead"
+    );
+}
+
+#[tokio::test]
+async fn test_parse_from_bytes() {
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let data = request::get(url).await.unwrap().bytes().await.unwrap();
+
+    let mut config = ParserConfig::new();
+    let pages = parse_from_bytes(&data, &mut config, false).unwrap();
+    assert!(Path::new(&config.pdf_path).exists());
+    let _ = config.clean_files();
+    assert!(!Path::new(&config.pdf_path).exists());
+
+    let mut config = ParserConfig::new();
+    let expected = parse(url, &mut config, false).await.unwrap();
+    let _ = config.clean_files();
+
+    assert_eq!(pages, expected);
+}
+
+#[test]
+fn test_parse_from_bytes_invalid_pdf() {
+    let mut config = ParserConfig::new();
+    let res = parse_from_bytes(b"not a pdf", &mut config, false);
+    assert!(matches!(res, Err(ParseError::InvalidPdf)));
+    let _ = config.clean_files();
+}
+
 #[tokio::test]
 async fn test_save_pdf_1() {
     let time = std::time::Instant::now();
@@ -315,3 +453,3859 @@ async fn test_pdf_to_json_3() {
     println!("{}", json);
     assert!(json.len() > 0);
 }
+
+fn block_from_lines(lines: &[&str]) -> Block {
+    let mut block = Block::new(0.0, 0.0, 100.0, 10.0 * lines.len() as f32);
+    for (i, text) in lines.iter().enumerate() {
+        let mut line = Line::new(0.0, 10.0 * i as f32, 100.0, 10.0);
+        for (j, word) in text.split_whitespace().enumerate() {
+            line.add_word(
+                word.to_string(),
+                10.0 * j as f32,
+                10.0 * i as f32,
+                8.0,
+                10.0,
+            );
+        }
+        block.lines.push(line);
+    }
+    return block;
+}
+
+#[test]
+fn test_block_get_text_hyphen_lowercase_continuation() {
+    let block = block_from_lines(&["these chal-", "lenges remain"]);
+    assert_eq!(block.get_text(), "these challenges remain");
+
+    let block = block_from_lines(&["the COVID-", "19 pandemic"]);
+    assert_eq!(block.get_text(), "the COVID-19 pandemic");
+}
+
+#[test]
+fn test_block_get_text_with_dictionary() {
+    let block = block_from_lines(&["these chal-", "lenges remain"]);
+    assert_eq!(block.get_text_with_dictionary(), "these challenges remain");
+
+    let block = block_from_lines(&["a state-", "of-the-art model"]);
+    assert_eq!(block.get_text(), "a stateof-the-art model");
+    assert_eq!(block.get_text_with_dictionary(), "a state-of-the-art model");
+
+    let block = block_from_lines(&["the COVID-", "19 pandemic"]);
+    assert_eq!(block.get_text_with_dictionary(), "the COVID-19 pandemic");
+
+    assert!(is_english_word("Challenges"));
+    assert!(is_english_word("studies"));
+    assert!(is_english_word("improved"));
+    assert!(!is_english_word("stateof"));
+
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&["a state-", "of-the-art model"]);
+    block.section = "Introduction".to_string();
+    page.blocks.push(block);
+    let mut config = ParserConfig::new();
+    config.dictionary_hyphenation = true;
+    let sections = Section::from_pages_with_config(&vec![page], &config);
+    assert_eq!(sections[0].get_text(), "a state-of-the-art model");
+}
+
+#[test]
+fn test_block_get_text_dash_line_end() {
+    let block = block_from_lines(&["see pages 10–", "12 for details"]);
+    assert_eq!(block.get_text(), "see pages 10– 12 for details");
+
+    let block = block_from_lines(&["the model—", "which is large"]);
+    assert_eq!(block.get_text(), "the model— which is large");
+}
+
+#[tokio::test]
+async fn test_extract_references_text() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let text = extract_references_text(url, &mut config).await.unwrap();
+
+    assert!(text.len() > 0);
+    assert!(text.contains("Jimmy Lei Ba"));
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_section_content_types() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (section, text, block_type) in [
+        ("Introduction", "1. Introduction", BlockType::Header),
+        ("Introduction", "We propose a model.", BlockType::Body),
+        ("Introduction", "Figure 1: The model.", BlockType::Caption),
+        ("Method", "2. Method", BlockType::Header),
+        ("Method", "The method works.", BlockType::Body),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        block.block_type = block_type;
+        page.blocks.push(block);
+    }
+    let pages = vec![page];
+
+    let sections = Section::from_pages(&pages);
+    assert!(sections.iter().all(|section| section.content_types.is_empty()));
+    assert!(!serde_json::to_string(&sections).unwrap().contains("content_types"));
+
+    let mut config = ParserConfig::new();
+    config.include_block_types = true;
+    let sections = Section::from_pages_with_config(&pages, &config);
+    for section in sections.iter() {
+        assert_eq!(section.contents.len(), section.content_types.len());
+    }
+    assert_eq!(
+        sections[0].content_types,
+        vec![BlockType::Header, BlockType::Body]
+    );
+    assert_eq!(
+        sections[1].content_types,
+        vec![BlockType::Header, BlockType::Body]
+    );
+}
+
+#[test]
+fn test_section_classified_blocks() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (text, block_type) in [
+        ("1. Introduction", BlockType::Header),
+        ("We propose a model.", BlockType::Body),
+        ("Figure 1: The model.", BlockType::Caption),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = "Introduction".to_string();
+        block.block_type = block_type;
+        page.blocks.push(block);
+    }
+    let pages = vec![page];
+
+    let sections = Section::from_pages(&pages);
+    assert!(sections[0].blocks.is_empty());
+    assert!(!serde_json::to_string(&sections).unwrap().contains("\"blocks\""));
+
+    let mut config = ParserConfig::new();
+    config.include_block_types = true;
+    let sections = Section::from_pages_with_config(&pages, &config);
+    assert_eq!(
+        sections[0].blocks,
+        vec![
+            ClassifiedBlock {
+                text: "1. Introduction".to_string(),
+                block_type: BlockType::Header,
+            },
+            ClassifiedBlock {
+                text: "We propose a model.".to_string(),
+                block_type: BlockType::Body,
+            },
+            ClassifiedBlock {
+                text: "Figure 1: The model.".to_string(),
+                block_type: BlockType::Caption,
+            },
+        ]
+    );
+    let json = serde_json::to_string(&sections).unwrap();
+    assert!(json.contains(r#"{"text":"Figure 1: The model.","block_type":"Caption"}"#));
+}
+
+#[test]
+fn test_section_spans() {
+    let mut pages = vec![Page::new(600.0, 800.0, 1), Page::new(600.0, 800.0, 2)];
+    for (page, section, text, y) in [
+        (0, "Introduction", "We propose a model.", 100.0),
+        (0, "Method", "The method is sim-", 500.0),
+        (1, "Method", "ple and fast.", 80.0),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.y = y;
+        block.section = section.to_string();
+        pages[page].blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&pages);
+    assert!(sections.iter().all(|section| section.spans.is_empty()));
+
+    let mut config = ParserConfig::new();
+    config.track_provenance = true;
+    let sections = Section::from_pages_with_config(&pages, &config);
+    assert_eq!(sections[0].spans.len(), 1);
+    assert_eq!(sections[0].spans[0].page, 1);
+    assert_eq!(sections[0].spans[0].coord.top_left.y, 100.0);
+
+    // the paragraph continued on the next page has one span per block
+    let method = &sections[1];
+    assert_eq!(method.contents, vec!["The method is simple and fast."]);
+    assert_eq!(
+        method.spans.iter().map(|span| span.page).collect::<Vec<PageNumber>>(),
+        vec![1, 2]
+    );
+    let (start, end) = method.spans[1].text_range;
+    assert_eq!(
+        &method.get_text()[start..end],
+        "The method is simple and fast."
+    );
+}
+
+#[test]
+fn test_line_fix_char_spacing() {
+    let mut line = Line::new(0.0, 0.0, 100.0, 10.0);
+    let mut x = 0.0;
+    for c in "The model".chars() {
+        if c == ' ' {
+            x += 4.0;
+            continue;
+        }
+        line.add_word(c.to_string(), x, 0.0, 5.0, 10.0);
+        x += 5.5;
+    }
+    assert_eq!(line.get_text(), "T h e m o d e l");
+
+    line.fix_char_spacing();
+    assert_eq!(line.get_text(), "The model");
+    assert_eq!(line.words.len(), 2);
+    assert_eq!(line.words[0].x, 0.0);
+    assert_eq!(line.words[0].width, 16.0);
+
+    let mut line = Line::new(0.0, 0.0, 100.0, 10.0);
+    line.add_word("The".to_string(), 0.0, 0.0, 15.0, 10.0);
+    line.add_word("model".to_string(), 17.0, 0.0, 25.0, 10.0);
+    line.fix_char_spacing();
+    assert_eq!(line.get_text(), "The model");
+}
+
+#[test]
+fn test_section_normalize_title() {
+    assert_eq!(Section::normalize_title("5 Conclusions"), "conclusion");
+    assert_eq!(
+        Section::normalize_title("Concluding  Remarks."),
+        "conclusion"
+    );
+    assert_eq!(Section::normalize_title("6.1 Limitation"), "limitations");
+    assert_eq!(
+        Section::normalize_title("Model Architecture"),
+        "model architecture"
+    );
+
+    let section = Section {
+        title: "Conclusions".to_string(),
+        ..Default::default()
+    };
+    assert!(section.matches_title("Conclusion"));
+    assert!(!section.matches_title("Limitations"));
+}
+
+#[tokio::test]
+async fn test_extract_sections() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/2308.10379";
+    let sections =
+        extract_sections(url, &mut config, &["Conclusion", "Limitations"]).await.unwrap();
+
+    let titles = sections.iter().map(|section| section.title.clone()).collect::<Vec<String>>();
+    assert_eq!(titles, vec!["Conclusion", "Limitations"]);
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_parser_config_builder() {
+    let config = ParserConfig::new();
+    assert!(config.pdf_path.starts_with("/tmp/pdf_"));
+    assert_eq!(config.dpi, 72);
+    assert_eq!(config.render_zoom(), 1.0);
+    assert!(config.keep_intermediate_files);
+
+    let config = ParserConfig::builder()
+        .temp_dir(PathBuf::from("/var/tmp/rsrpp"))
+        .dpi(144)
+        .keep_intermediate_files(false)
+        .build()
+        .unwrap();
+    assert!(config.pdf_path.starts_with("/var/tmp/rsrpp/pdf_"));
+    assert!(config.pdf_path.ends_with(".pdf"));
+    assert!(config.pdf_text_path.starts_with("/var/tmp/rsrpp/pdf_"));
+    assert!(config.pdf_xml_path.starts_with("/var/tmp/rsrpp/pdf_"));
+    assert_eq!(config.dpi, 144);
+    assert_eq!(config.render_zoom(), 2.0);
+    assert!(!config.keep_intermediate_files);
+
+    // only the file name is rewritten for the sibling files
+    let config = ParserConfig::builder().temp_dir(PathBuf::from("/data/x.pdfs")).build().unwrap();
+    assert!(config.pdf_text_path.starts_with("/data/x.pdfs/pdf_"));
+    assert!(config.pdf_text_path.ends_with(".text.html"));
+    assert!(config.pdf_xml_path.starts_with("/data/x.pdfs/pdf_"));
+    assert!(config.pdf_xml_path.ends_with(".xml"));
+
+    assert!(matches!(
+        ParserConfig::builder().dpi(0).build(),
+        Err(ParseError::InvalidConfig(_))
+    ));
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let temp_dir = PathBuf::from(std::ffi::OsStr::from_bytes(b"/tmp/\xff"));
+        assert!(matches!(
+            ParserConfig::builder().temp_dir(temp_dir).build(),
+            Err(ParseError::InvalidConfig(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_parse_dpi_coordinates() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/parser/testdata/sample.pdf"
+    );
+    let mut config = ParserConfig::builder().keep_intermediate_files(false).build().unwrap();
+    let pages = parse(path, &mut config, false).await.unwrap();
+
+    let mut config =
+        ParserConfig::builder().dpi(144).keep_intermediate_files(false).build().unwrap();
+    let hires = parse(path, &mut config, false).await.unwrap();
+    assert_eq!(pages.len(), hires.len());
+
+    let words = |page: &Page| {
+        page.blocks
+            .iter()
+            .flat_map(|block| block.lines.iter())
+            .flat_map(|line| line.words.iter())
+            .map(|word| (word.text.clone(), word.x, word.y, word.width, word.height))
+            .collect::<Vec<(String, f32, f32, f32, f32)>>()
+    };
+    for (page, mut hires) in pages.iter().zip(hires.into_iter()) {
+        // the boxes and the page size are in the same unit
+        for (_, x, y, width, height) in words(&hires) {
+            assert!(x >= 0.0 && x + width <= hires.width);
+            assert!(y >= 0.0 && y + height <= hires.height);
+        }
+        hires.to_pdf_points(config.render_zoom());
+        assert!((hires.width - page.width).abs() < 1.0);
+        assert!((hires.height - page.height).abs() < 1.0);
+        let (expected, actual) = (words(page), words(&hires));
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual.iter()) {
+            assert_eq!(expected.0, actual.0);
+            assert!((expected.1 - actual.1).abs() < 1.0);
+            assert!((expected.2 - actual.2).abs() < 1.0);
+        }
+    }
+}
+
+#[test]
+fn test_reconcile_figures_missing_page() {
+    let mut config = ParserConfig::new();
+    let xml = r#"<pdf2xml><page number="1" width="612" height="792"></page><page number="2" width="612" height="792"></page><page number="3" width="612" height="792"></page></pdf2xml>"#;
+    std::fs::write(&config.pdf_xml_path, xml).unwrap();
+    config.pdf_info.insert("page_width".to_string(), "612".to_string());
+    config.pdf_info.insert("page_height".to_string(), "792".to_string());
+    config.pdf_figures.insert(1, "/tmp/figure-1.jpg".to_string());
+    config.pdf_figures.insert(3, "/tmp/figure-3.jpg".to_string());
+
+    reconcile_figures(&mut config, true).unwrap();
+
+    assert_eq!(config.pdf_figures.len(), 3);
+    let blank_path = config.pdf_figures.get(&2).unwrap().clone();
+    assert!(Path::new(&blank_path).exists());
+
+    config.pdf_figures.remove(&1);
+    config.pdf_figures.remove(&3);
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_page_numbers_beyond_127() {
+    let figures = (1..=150)
+        .map(|i| Path::new(&format!("/tmp/pdf_x-{:03}.jpg", i)).to_path_buf())
+        .collect::<Vec<_>>();
+    let page_numbers =
+        figures.iter().map(|path| figure_page_number(path).unwrap()).collect::<Vec<PageNumber>>();
+    assert_eq!(page_numbers, (1..=150).collect::<Vec<PageNumber>>());
+    assert!(figure_page_number(Path::new("/tmp/pdf_x.jpg")).is_err());
+
+    let mut config = ParserConfig::new();
+    let xml = (1..=150)
+        .map(|i| format!(r#"<page number="{}" width="612" height="792"></page>"#, i))
+        .collect::<String>();
+    std::fs::write(&config.pdf_xml_path, format!("<pdf2xml>{}</pdf2xml>", xml)).unwrap();
+    for (page_number, path) in page_numbers.iter().zip(figures.iter()).take(149) {
+        config.pdf_figures.insert(*page_number, path.to_str().unwrap().to_string());
+    }
+
+    reconcile_figures(&mut config, false).unwrap();
+
+    assert_eq!(config.pdf_figures.len(), 150);
+    let blank_path = config.pdf_figures.get(&150).unwrap().clone();
+    assert!(blank_path.ends_with("-150.jpg"));
+
+    for page_number in 1..=149 {
+        config.pdf_figures.remove(&page_number);
+    }
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_strip_section_number() {
+    assert_eq!(
+        strip_section_number("3D Reconstruction"),
+        "3D Reconstruction"
+    );
+    assert_eq!(strip_section_number("2.1 Method"), "Method");
+    assert_eq!(
+        strip_section_number("2.1.1 Encoder Stacks"),
+        "Encoder Stacks"
+    );
+    assert_eq!(strip_section_number("4. Experiments"), "Experiments");
+    assert_eq!(strip_section_number("2.5D Vision"), "2.5D Vision");
+    assert_eq!(
+        strip_section_number("3.5-inch Displays"),
+        "3.5-inch Displays"
+    );
+    assert_eq!(strip_section_number("5.2 2.5D Vision"), "2.5D Vision");
+    assert_eq!(strip_section_number("Introduction"), "Introduction");
+    assert_eq!(
+        strip_section_number("Training on 8 GPUs"),
+        "Training on 8 GPUs"
+    );
+}
+
+#[test]
+fn test_parse_collect_diagnostics() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Abstract".to_string()));
+
+    let mut pages = Vec::new();
+    for page_number in 1..=6 {
+        let mut page = Page::new(600.0, 800.0, page_number);
+        page.number_of_columns = 2;
+        let mut block = block_from_lines(&["The dominant sequence transduction models."]);
+        block.width = if page_number == 6 { 500.0 } else { 250.0 };
+        block.x = 50.0;
+        block.section = if page_number == 6 {
+            "Conclusion".to_string()
+        } else {
+            "Abstract".to_string()
+        };
+        page.blocks.push(block);
+        pages.push(page);
+    }
+
+    parse_collect_diagnostics(&mut config, &pages).unwrap();
+
+    assert!(config.diagnostics.contains(&ParseDiagnostic::LongSection {
+        title: "Abstract".to_string(),
+        pages: 5,
+    }));
+    assert!(config.diagnostics.contains(&ParseDiagnostic::MissingReferences));
+    assert!(config.diagnostics.contains(&ParseDiagnostic::LowColumnConfidence { page: 6 }));
+    assert_eq!(config.diagnostics.len(), 3);
+}
+
+#[test]
+fn test_coordinate_is_contained_in_with_threshold() {
+    let table = Coordinate::from_rect(0.0, 100.0, 500.0, 300.0);
+    let near_table_line = Coordinate::from_object(0.0, 90.0, 500.0, 20.0);
+
+    assert!(near_table_line.is_contained_in(&table));
+    assert!(near_table_line.is_contained_in_with_threshold(&table, 0.3));
+    assert!(!near_table_line.is_contained_in_with_threshold(&table, 0.8));
+
+    let config = ParserConfig::new();
+    assert_eq!(config.table_containment_threshold, 0.3);
+}
+
+#[test]
+fn test_extract_toc() {
+    let mut page = Page::new(600.0, 800.0, 2);
+    let mut block = block_from_lines(&[
+        "Contents",
+        "1 Introduction . . . . . . . . . 3",
+        "1.1 Scope ........ 4",
+        "2 Method .......... 7",
+        "This line is not part of the table of contents.",
+    ]);
+    block.lines[2].x = 20.0;
+    page.blocks.push(block);
+
+    let toc = extract_toc(&vec![page]);
+    assert_eq!(
+        toc,
+        vec![
+            TocEntry {
+                title: "Introduction".to_string(),
+                page: 3,
+                level: 1
+            },
+            TocEntry {
+                title: "Scope".to_string(),
+                page: 4,
+                level: 2
+            },
+            TocEntry {
+                title: "Method".to_string(),
+                page: 7,
+                level: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_extract_toc_page_number_overflow() {
+    let mut page = Page::new(600.0, 800.0, 2);
+    page.blocks.push(block_from_lines(&[
+        "1 Introduction . . . . . . . . . 3",
+        "Checksum .......... 123456789012345678901234567890",
+    ]));
+
+    let toc = extract_toc(&vec![page]);
+    assert_eq!(toc.len(), 1);
+    assert_eq!(toc[0].title, "Introduction");
+}
+
+#[test]
+fn test_normalize_punctuation_ascii() {
+    assert_eq!(normalize_punctuation_ascii("‘single’"), "'single'");
+    assert_eq!(normalize_punctuation_ascii("‚low‛"), "'low'");
+    assert_eq!(normalize_punctuation_ascii("“double”"), "\"double\"");
+    assert_eq!(normalize_punctuation_ascii("„low‟"), "\"low\"");
+    assert_eq!(normalize_punctuation_ascii("pages 10–12"), "pages 10-12");
+    assert_eq!(normalize_punctuation_ascii("model—which"), "model--which");
+    assert_eq!(
+        normalize_punctuation_ascii("“x” <math>a–b ‘c’</math> “y”"),
+        "\"x\" <math>a–b ‘c’</math> \"y\""
+    );
+}
+
+#[test]
+fn test_page_render_text_layout() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut left = block_from_lines(&["Left column", "second line"]);
+    for line in left.lines.iter_mut() {
+        line.x = 0.0;
+    }
+    let mut right = block_from_lines(&["Right column", "more text"]);
+    for line in right.lines.iter_mut() {
+        line.x = 300.0;
+        line.y += 1.0;
+    }
+    page.blocks.push(left);
+    page.blocks.push(right);
+
+    let text = page.render_text_layout(40);
+    assert_eq!(
+        text,
+        "Left column         Right column\nsecond line         more text"
+    );
+}
+
+#[test]
+fn test_section_aliases() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (section, text) in [
+        ("Introduction", "We propose a model."),
+        ("Prior Work", "Earlier models exist."),
+        ("Method", "The method works."),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let mut config = ParserConfig::new();
+    config.section_aliases.insert("Prior Work".to_string(), "Related Work".to_string());
+    let sections = Section::from_pages_with_config(&vec![page], &config);
+
+    assert_eq!(sections[0].title, "Introduction");
+    assert_eq!(sections[0].raw_title, None);
+    assert_eq!(sections[1].title, "Related Work");
+    assert_eq!(sections[1].raw_title, Some("Prior Work".to_string()));
+    assert_eq!(sections[1].contents, vec!["Earlier models exist."]);
+    assert_eq!(sections[2].title, "Method");
+
+    // keys that normalize alike resolve to the first key in order, on every run
+    let mut aliases = BTreeMap::new();
+    aliases.insert("prior work".to_string(), "Background".to_string());
+    aliases.insert("Prior Work".to_string(), "Related Work".to_string());
+    for _ in 0..10 {
+        assert_eq!(
+            Section::resolve_alias("PRIOR WORK", &aliases),
+            "Related Work"
+        );
+    }
+}
+
+fn block_at(x: f32, y: f32, width: f32, lines: &[&str]) -> Block {
+    let mut block = block_from_lines(lines);
+    block.x = x;
+    block.y = y;
+    block.width = width;
+    for (i, line) in block.lines.iter_mut().enumerate() {
+        line.x = x;
+        line.y = y + 10.0 * i as f32;
+        line.width = width;
+        for word in line.words.iter_mut() {
+            word.x += x;
+            word.y = line.y;
+        }
+    }
+    return block;
+}
+
+#[test]
+fn test_parse_extract_textarea_keeps_narrow_abstract() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Abstract".to_string()));
+    config.sections.push((1, "Introduction".to_string()));
+
+    let mut page = Page::new(600.0, 800.0, 1);
+    page.blocks.push(block_at(250.0, 100.0, 100.0, &["Abstract"]));
+    page.blocks.push(block_at(
+        250.0,
+        120.0,
+        100.0,
+        &[
+            "We propose a new",
+            "network architecture",
+            "based on attention.",
+        ],
+    ));
+    page.blocks.push(block_at(50.0, 200.0, 500.0, &["1 Introduction"]));
+    page.blocks.push(block_at(50.0, 220.0, 80.0, &["Footnote"]));
+    page.blocks.push(block_at(
+        50.0,
+        240.0,
+        500.0,
+        &["Recurrent neural networks have been firmly established."],
+    ));
+    let mut pages = vec![page];
+
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+
+    let texts = pages[0].blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+    assert_eq!(
+        texts,
+        vec![
+            "Abstract",
+            "We propose a new network architecture based on attention.",
+            "1 Introduction",
+            "Recurrent neural networks have been firmly established.",
+        ]
+    );
+}
+
+#[test]
+fn test_partition_document() {
+    let sections = [
+        "Abstract",
+        "Introduction",
+        "Method",
+        "Conclusion",
+        "Acknowledgments",
+        "References",
+        "Appendix A Proofs",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, title)| Section {
+        index: i,
+        title: title.to_string(),
+        ..Default::default()
+    })
+    .collect::<Vec<Section>>();
+
+    let parts = partition_document(&sections);
+    let titles = |sections: &Vec<Section>| {
+        sections.iter().map(|section| section.title.clone()).collect::<Vec<String>>()
+    };
+    assert_eq!(titles(&parts.front), vec!["Abstract"]);
+    assert_eq!(
+        titles(&parts.body),
+        vec!["Introduction", "Method", "Conclusion"]
+    );
+    assert_eq!(
+        titles(&parts.back),
+        vec!["Acknowledgments", "References", "Appendix A Proofs"]
+    );
+}
+
+#[test]
+fn test_drop_sections() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (section, text) in [
+        ("Conclusion", "We conclude."),
+        ("Impact Statement", "This work has no impact."),
+        ("References", "[1] A paper."),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let mut config = ParserConfig::new();
+    config.drop_sections.push("impact statement".to_string());
+    let sections = Section::from_pages_with_config(&vec![page], &config);
+
+    let titles = sections.iter().map(|section| section.title.clone()).collect::<Vec<String>>();
+    assert_eq!(titles, vec!["Conclusion", "References"]);
+}
+
+#[test]
+fn test_get_text_area_without_lines() {
+    let mut pages = Vec::new();
+    for page_number in 1..=3 {
+        let mut page = Page::new(600.0, 800.0, page_number);
+        page.add_block(50.0, 50.0, 500.0, 700.0);
+        pages.push(page);
+    }
+
+    let text_area = get_text_area(&pages);
+    assert_eq!(text_area, Coordinate::from_rect(0.0, 0.0, 600.0, 800.0));
+
+    pages[1].blocks.push(block_at(50.0, 100.0, 400.0, &["Some text"]));
+    let text_area = get_text_area(&pages);
+    assert_eq!(text_area, Coordinate::from_rect(50.0, 100.0, 450.0, 110.0));
+}
+
+#[test]
+fn test_parse_caption() {
+    assert_eq!(
+        parse_caption("Table 12. Results on the test set"),
+        Some((
+            CaptionKind::Table,
+            "12".to_string(),
+            "Results on the test set".to_string()
+        ))
+    );
+    assert_eq!(
+        parse_caption("Fig. 4: The Transformer"),
+        Some((
+            CaptionKind::Figure,
+            "4".to_string(),
+            "The Transformer".to_string()
+        ))
+    );
+    assert_eq!(
+        parse_caption("Figure 3: The architecture"),
+        Some((
+            CaptionKind::Figure,
+            "3".to_string(),
+            "The architecture".to_string()
+        ))
+    );
+    assert_eq!(parse_caption("Table 1 shows the results."), None);
+    assert_eq!(parse_caption("The figure shows"), None);
+}
+
+#[test]
+fn test_caption_patterns() {
+    let german = CaptionPatterns::with_german();
+    assert_eq!(
+        german.parse("Abbildung 2: Die Architektur"),
+        Some((
+            CaptionKind::Figure,
+            "2".to_string(),
+            "Die Architektur".to_string()
+        ))
+    );
+    assert_eq!(
+        german.parse("Tabelle 3. Ergebnisse"),
+        Some((
+            CaptionKind::Table,
+            "3".to_string(),
+            "Ergebnisse".to_string()
+        ))
+    );
+    // the English patterns are kept
+    assert_eq!(
+        german.parse("Figure 1: The model").unwrap().0,
+        CaptionKind::Figure
+    );
+
+    let japanese = CaptionPatterns::with_japanese();
+    assert_eq!(
+        japanese.parse("図 2 モデルの構成"),
+        Some((
+            CaptionKind::Figure,
+            "2".to_string(),
+            "モデルの構成".to_string()
+        ))
+    );
+    assert_eq!(
+        japanese.parse("表1：実験結果"),
+        Some((CaptionKind::Table, "1".to_string(), "実験結果".to_string()))
+    );
+    assert_eq!(CaptionPatterns::default().parse("図 2 モデルの構成"), None);
+
+    let custom = CaptionPatterns::new(&[(
+        CaptionKind::Figure,
+        r"^Plate (?P<label>\d+)\. (?P<text>.*)$",
+    )])
+    .unwrap();
+    assert_eq!(
+        custom.parse("Plate 7. A map"),
+        Some((CaptionKind::Figure, "7".to_string(), "A map".to_string()))
+    );
+    assert!(CaptionPatterns::new(&[(CaptionKind::Figure, r"(")]).is_err());
+}
+
+#[test]
+fn test_parse_classify_blocks_caption_patterns() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    page.blocks.push(block_from_lines(&[
+        "Abbildung 2: Die Architektur des Modells.",
+    ]));
+    page.blocks.push(block_from_lines(&["図 2 モデルの構成"]));
+    page.blocks.push(block_from_lines(&["Figure 1: The model."]));
+
+    let mut config = ParserConfig::new();
+    let mut pages = vec![page.clone()];
+    parse_classify_blocks(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks[0].block_type, BlockType::Body);
+    assert_eq!(pages[0].blocks[1].block_type, BlockType::Body);
+    assert_eq!(pages[0].blocks[2].block_type, BlockType::Caption);
+
+    config.caption_patterns = CaptionPatterns::with_german();
+    let mut pages = vec![page.clone()];
+    parse_classify_blocks(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks[0].block_type, BlockType::Caption);
+    assert_eq!(pages[0].blocks[1].block_type, BlockType::Body);
+
+    config.caption_patterns = CaptionPatterns::with_japanese();
+    let mut pages = vec![page];
+    parse_classify_blocks(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks[1].block_type, BlockType::Caption);
+    assert_eq!(pages[0].blocks[2].block_type, BlockType::Caption);
+}
+
+#[test]
+fn test_parse_classify_blocks_subsection_headings() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    page.blocks.push(block_from_lines(&["3.1 Scaled Dot-Product Attention"]));
+    page.blocks.push(block_from_lines(&[
+        "We call our particular attention Scaled Dot-Product Attention.",
+    ]));
+    page.blocks.push(block_from_lines(&["A New Approach to Parsing"]));
+
+    let mut config = ParserConfig::new();
+    let mut pages = vec![page];
+    parse_classify_blocks(&mut config, &mut pages).unwrap();
+    let types = pages[0].blocks.iter().map(|block| block.block_type.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![BlockType::Header, BlockType::Body, BlockType::Body]
+    );
+}
+
+#[test]
+fn test_parse_classify_blocks_footnotes() {
+    let line_at = |text: &str, y: f32, font_size: f32| {
+        let mut line = Line::new(50.0, y, 400.0, font_size);
+        for (i, word) in text.split_whitespace().enumerate() {
+            line.add_word(word.to_string(), 50.0 + 30.0 * i as f32, y, 25.0, font_size);
+        }
+        return line;
+    };
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut body = Block::new(50.0, 100.0, 400.0, 40.0);
+    body.lines.push(line_at(
+        "We propose a new model for translation.",
+        100.0,
+        10.0,
+    ));
+    body.lines.push(line_at("It relies on attention alone.", 115.0, 10.0));
+    page.blocks.push(body);
+
+    // the last body block of the page absorbed the footnote
+    let mut last = Block::new(50.0, 600.0, 400.0, 160.0);
+    last.lines.push(line_at("The model is trained on eight GPUs.", 600.0, 10.0));
+    last.lines.push(line_at("Training takes three days.", 615.0, 10.0));
+    last.lines.push(line_at("The footnotes follow.", 730.0, 7.0));
+    last.lines.push(line_at("1 Code is available online.", 740.0, 7.0));
+    last.lines.push(line_at("2 Work done at Google", 750.0, 7.0));
+    last.lines.push(line_at("Brain.", 760.0, 7.0));
+    page.blocks.push(last);
+    // small print without a marker
+    let mut notice = Block::new(50.0, 780.0, 400.0, 10.0);
+    notice.lines.push(line_at("Preprint. Under review.", 780.0, 7.0));
+    page.blocks.push(notice);
+    for block in page.blocks.iter_mut() {
+        block.section = "Introduction".to_string();
+    }
+
+    let mut config = ParserConfig::new();
+    let mut pages = vec![page];
+    parse_classify_blocks(&mut config, &mut pages).unwrap();
+    let types = pages[0].blocks.iter().map(|block| block.block_type.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![
+            BlockType::Body,
+            BlockType::Body,
+            BlockType::Footnote,
+            BlockType::Body
+        ]
+    );
+    assert_eq!(pages[0].blocks[1].lines.len(), 3);
+    assert_eq!(pages[0].blocks[2].y, 740.0);
+
+    let sections = Section::from_pages(&pages);
+    assert_eq!(
+        sections[0].footnotes,
+        vec![
+            "1 Code is available online.",
+            "2 Work done at Google Brain."
+        ]
+    );
+    assert!(sections[0].contents.iter().all(|text| !text.contains("Code is available")));
+    assert_eq!(sections[0].contents.len(), 3);
+}
+
+#[test]
+fn test_section_captions() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (text, block_type) in [
+        ("We propose a model.", BlockType::Body),
+        ("Figure 1: The model.", BlockType::Caption),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = "Introduction".to_string();
+        block.block_type = block_type;
+        page.blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&vec![page]);
+    assert_eq!(sections[0].contents, vec!["We propose a model."]);
+    assert_eq!(
+        sections[0].captions,
+        vec![Caption {
+            kind: CaptionKind::Figure,
+            label: "1".to_string(),
+            text: "The model.".to_string(),
+        }]
+    );
+    let json = serde_json::to_string(&sections).unwrap();
+    assert!(json.contains(r#""captions":[{"kind":"Figure","label":"1","text":"The model."}]"#));
+}
+
+#[test]
+fn test_section_dedup_consecutive_contents() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for text in [
+        "We propose a model.",
+        "We propose  a model. ",
+        "It works well.",
+        "We propose a model.",
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = "Abstract".to_string();
+        page.blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&vec![page]);
+    assert_eq!(
+        sections[0].contents,
+        vec![
+            "We propose a model.",
+            "It works well.",
+            "We propose a model."
+        ]
+    );
+}
+
+#[test]
+fn test_analyze_language() {
+    let english = vec![Section {
+        title: "Introduction".to_string(),
+        contents: vec![
+            "We propose a new model for the task of machine translation that is based on attention."
+                .to_string(),
+        ],
+        ..Default::default()
+    }];
+    let profile = analyze_language(&english);
+    assert_eq!(profile.primary, Lang::English);
+    assert_eq!(profile.script, Script::Latin);
+    assert!(profile.confidence > 0.9);
+
+    let japanese = vec![Section {
+        title: "はじめに".to_string(),
+        contents: vec!["本研究では、注意機構に基づく新しい翻訳モデルを提案する。".to_string()],
+        ..Default::default()
+    }];
+    let profile = analyze_language(&japanese);
+    assert_eq!(profile.primary, Lang::Japanese);
+    assert_eq!(profile.script, Script::Cjk);
+
+    let chinese = vec![Section {
+        title: "引言".to_string(),
+        contents: vec!["本文提出了一种基于注意力机制的翻译模型。".to_string()],
+        ..Default::default()
+    }];
+    assert_eq!(analyze_language(&chinese).primary, Lang::Chinese);
+
+    assert_eq!(analyze_language(&[]).primary, Lang::Unknown);
+}
+
+#[test]
+fn test_analyze_page_language() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&[
+        "We propose a new model for the task of",
+        "machine translation that is based on attention.",
+    ]);
+    block.section = "Introduction".to_string();
+    page.blocks.push(block);
+    let profile = analyze_page_language(&[page.clone()]);
+    assert_eq!(profile, analyze_language(&Section::from_pages(&vec![page])));
+    assert_eq!(profile.primary, Lang::English);
+
+    let mut page = Page::new(600.0, 800.0, 1);
+    page.blocks.push(block_from_lines(&[
+        "本研究では、注意機構に基づく",
+        "新しい翻訳モデルを提案する。",
+    ]));
+    assert_eq!(analyze_page_language(&[page]).primary, Lang::Japanese);
+
+    assert_eq!(analyze_page_language(&[]).primary, Lang::Unknown);
+}
+
+#[test]
+fn test_section_cjk_joins_lines_without_spaces() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&["本研究では新しい-", "モデルを提案する。"]);
+    block.section = "はじめに".to_string();
+    page.blocks.push(block);
+    let pages = vec![page];
+
+    let mut config = ParserConfig::new();
+    config.language = Some(LanguageProfile {
+        primary: Lang::Japanese,
+        script: Script::Cjk,
+        confidence: 1.0,
+    });
+    let sections = Section::from_pages_with_config(&pages, &config);
+    assert_eq!(
+        sections[0].contents,
+        vec!["本研究では新しい-モデルを提案する。"]
+    );
+}
+
+#[test]
+fn test_to_pdf_points() {
+    let coordinate = Coordinate::from_rect(100.0, 200.0, 300.0, 400.0);
+    assert_eq!(
+        coordinate.to_pdf_points(2.0),
+        Coordinate::from_rect(50.0, 100.0, 150.0, 200.0)
+    );
+
+    let mut page = Page::new(1224.0, 1584.0, 1);
+    page.blocks.push(block_at(100.0, 200.0, 400.0, &["Hello world"]));
+    page.tables.push(DetectedTable::new(coordinate.clone(), 1.0));
+    page.captioned_figures.push(CaptionedFigure {
+        caption: "Figure 1: Overview".to_string(),
+        kind: CaptionKind::Figure,
+        label: "1".to_string(),
+        region: coordinate,
+        page: 1,
+    });
+    page.to_pdf_points(2.0);
+    assert_eq!(page.width, 612.0);
+    assert_eq!(page.height, 792.0);
+    assert_eq!(page.blocks[0].x, 50.0);
+    assert_eq!(page.blocks[0].lines[0].y, 100.0);
+    assert_eq!(page.blocks[0].lines[0].words[0].x, 50.0);
+    assert_eq!(page.tables[0].coord.bottom_right.y, 200.0);
+    assert_eq!(
+        page.captioned_figures[0].region,
+        Coordinate::from_rect(50.0, 100.0, 150.0, 200.0)
+    );
+
+    let json = serde_json::to_string(&page).unwrap();
+    assert!(json.contains(r#""width":612.0"#));
+}
+
+#[test]
+fn test_adjst_columns_uses_page_width() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Introduction".to_string()));
+    config.pdf_info.insert("page_width".to_string(), "612".to_string());
+
+    let mut portrait = Page::new(612.0, 792.0, 1);
+    portrait.blocks.push(block_at(320.0, 100.0, 250.0, &["right column"]));
+    portrait.blocks.push(block_at(50.0, 400.0, 250.0, &["left column"]));
+
+    // a landscape page: x = 350 is in the left column of this page, not the right one
+    let mut landscape = Page::new(1000.0, 612.0, 2);
+    landscape.blocks.push(block_at(350.0, 100.0, 100.0, &["left table cell"]));
+    landscape.blocks.push(block_at(600.0, 100.0, 100.0, &["right table cell"]));
+    landscape.blocks.push(block_at(50.0, 400.0, 100.0, &["left column"]));
+
+    let mut pages = vec![portrait, landscape];
+    adjst_columns(&mut pages, &config);
+
+    let texts =
+        |page: &Page| page.blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+    assert_eq!(texts(&pages[0]), vec!["left column", "right column"]);
+    assert_eq!(
+        texts(&pages[1]),
+        vec!["left table cell", "left column", "right table cell"]
+    );
+}
+
+#[test]
+fn test_parse_embed_page_images() {
+    let mut config = ParserConfig::new();
+    let fig_path = "/tmp/rsrpp-embed-test-1.jpg";
+    std::fs::write(fig_path, [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]).unwrap();
+    config.pdf_figures.insert(1, fig_path.to_string());
+    let mut pages = vec![Page::new(612.0, 792.0, 1)];
+
+    parse_embed_page_images(&config, &mut pages).unwrap();
+
+    let image_data = pages[0].image_data.clone().unwrap();
+    let decoded = base64::engine::general_purpose::STANDARD.decode(&image_data).unwrap();
+    assert_eq!(decoded, vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]);
+    let _ = std::fs::remove_file(fig_path);
+}
+
+#[test]
+fn test_extract_tables_skips_missing_image() {
+    let mut tables = Vec::new();
+    let result = extract_tables("/tmp/rsrpp-no-such-figure.jpg", &mut tables, 612, 792);
+    assert!(result.is_err());
+    assert!(tables.is_empty());
+
+    // the skipped page is reported as a diagnostic instead of being printed
+    let mut config = ParserConfig::new();
+    config.pdf_figures.insert(1, "/tmp/rsrpp-no-such-figure.jpg".to_string());
+    let tables = detect_tables(&mut config, &vec![(1, 612.0, 792.0)], 1);
+    assert!(tables[0].is_empty());
+    assert!(matches!(
+        config.diagnostics[..],
+        [ParseDiagnostic::TablesSkipped { page: 1, .. }]
+    ));
+}
+
+#[test]
+fn test_extract_tables_skips_tiny_image() {
+    let image_path = "/tmp/rsrpp-tiny-figure.png";
+    let image = Mat::new_rows_cols_with_default(
+        1,
+        1,
+        opencv::core::CV_8UC3,
+        opencv::core::Scalar::all(255.0),
+    )
+    .unwrap();
+    imgcodecs::imwrite_def(image_path, &image).unwrap();
+
+    let mut tables = Vec::new();
+    let result = extract_tables(image_path, &mut tables, 612, 792);
+    assert!(result.unwrap_err().to_string().contains("too small"));
+    assert!(tables.is_empty());
+
+    let _ = std::fs::remove_file(image_path);
+}
+
+#[test]
+fn test_group_table_lines_bordered_grid() {
+    // a bordered 3x3 grid: 4 horizontal and 4 vertical rules
+    let mut segments = Vec::new();
+    for i in 0..4 {
+        let y = 200.0 + 40.0 * i as f32;
+        segments.push((Point::new(100.0, y), Point::new(500.0, y)));
+        let x = 100.0 + 400.0 / 3.0 * i as f32;
+        segments.push((Point::new(x, 200.0), Point::new(x, 320.0)));
+    }
+    // an unrelated horizontal rule, e.g. a footnote separator
+    segments.push((Point::new(60.0, 700.0), Point::new(200.0, 700.0)));
+
+    let tables = group_table_lines(&segments, 612);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(
+        tables[0].coord,
+        Coordinate::from_rect(100.0, 200.0, 500.0, 320.0)
+    );
+    assert!(tables[0].confidence > 0.5);
+}
+
+#[test]
+fn test_group_table_lines_single_frame() {
+    // a frame around a plot or an algorithm has only one cell
+    let segments = vec![
+        (Point::new(100.0, 200.0), Point::new(500.0, 200.0)),
+        (Point::new(100.0, 400.0), Point::new(500.0, 400.0)),
+        (Point::new(100.0, 200.0), Point::new(100.0, 400.0)),
+        (Point::new(500.0, 200.0), Point::new(500.0, 400.0)),
+    ];
+    assert!(group_table_lines(&segments, 612).is_empty());
+}
+
+#[test]
+fn test_collect_table_lines_drops_empty_tables() {
+    let mut page = Page::new(612.0, 792.0, 1);
+    let mut block = Block::new(100.0, 200.0, 300.0, 20.0);
+    block.lines.push(table_line(200.0, &[(100.0, "Model"), (250.0, "BLEU")]));
+    block.lines.push(table_line(400.0, &[(100.0, "Body text below the table.")]));
+    page.blocks.push(block);
+    page.tables = vec![
+        DetectedTable::new(Coordinate::from_rect(90.0, 190.0, 400.0, 220.0), 0.9),
+        DetectedTable::new(Coordinate::from_rect(90.0, 600.0, 400.0, 700.0), 0.9),
+    ];
+
+    collect_table_lines(&mut page, 0.3);
+    assert_eq!(page.tables.len(), 1);
+    assert_eq!(page.tables[0].rows, vec![vec!["Model", "BLEU"]]);
+    assert_eq!(page.blocks[0].get_text(), "Body text below the table.");
+}
+
+#[test]
+fn test_group_table_lines_short_rules() {
+    // a small grid whose horizontal rules are too short to be grouped by length
+    let mut segments = Vec::new();
+    for i in 0..3 {
+        let y = 400.0 + 20.0 * i as f32;
+        segments.push((Point::new(300.0, y), Point::new(400.0, y)));
+        let x = 300.0 + 50.0 * i as f32;
+        segments.push((Point::new(x, 400.0), Point::new(x, 440.0)));
+    }
+
+    let tables = group_table_lines(&segments, 612);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(
+        tables[0].coord,
+        Coordinate::from_rect(300.0, 400.0, 400.0, 440.0)
+    );
+
+    // vertical rules that do not cross the horizontal ones do not form a grid
+    let apart = segments
+        .iter()
+        .map(|(p1, p2)| {
+            if p1.x == p2.x {
+                (
+                    Point::new(p1.x, p1.y + 100.0),
+                    Point::new(p2.x, p2.y + 100.0),
+                )
+            } else {
+                (p1.clone(), p2.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+    assert!(group_table_lines(&apart, 612).is_empty());
+}
+
+#[test]
+fn test_extract_tables_bordered_grid() {
+    let image_path = "/tmp/rsrpp-bordered-grid.png";
+    let mut image = Mat::new_rows_cols_with_default(
+        792,
+        612,
+        opencv::core::CV_8UC3,
+        opencv::core::Scalar::all(255.0),
+    )
+    .unwrap();
+    let black = opencv::core::Scalar::all(0.0);
+    for i in 0..4 {
+        let y = 200 + 40 * i;
+        imgproc::line(
+            &mut image,
+            opencv::core::Point::new(100, y),
+            opencv::core::Point::new(500, y),
+            black,
+            1,
+            imgproc::LINE_8,
+            0,
+        )
+        .unwrap();
+        let x = 100 + 400 * i / 3;
+        imgproc::line(
+            &mut image,
+            opencv::core::Point::new(x, 200),
+            opencv::core::Point::new(x, 320),
+            black,
+            1,
+            imgproc::LINE_8,
+            0,
+        )
+        .unwrap();
+    }
+    imgcodecs::imwrite_def(image_path, &image).unwrap();
+
+    let mut tables = Vec::new();
+    extract_tables(image_path, &mut tables, 612, 792).unwrap();
+    assert_eq!(tables.len(), 1);
+    let coord = &tables[0].coord;
+    assert!((coord.top_left.x - 100.0).abs() < 5.0);
+    assert!((coord.top_left.y - 200.0).abs() < 5.0);
+    assert!((coord.bottom_right.x - 500.0).abs() < 5.0);
+    assert!((coord.bottom_right.y - 320.0).abs() < 5.0);
+
+    let _ = std::fs::remove_file(image_path);
+}
+
+#[test]
+fn test_section_preview() {
+    let section = Section {
+        title: "Abstract".to_string(),
+        contents: vec![
+            "We propose the Transformer,".to_string(),
+            "where <math>a + b</math> is the loss of the model.".to_string(),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(section.preview(4), "We propose the Transformer,...");
+    assert_eq!(
+        section.preview(6),
+        "We propose the Transformer, where <math>a + b</math>..."
+    );
+    assert_eq!(
+        section.preview(100),
+        "We propose the Transformer, where <math>a + b</math> is the loss of the model."
+    );
+}
+
+#[test]
+fn test_parse_extract_secsions_inline_abstract() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Introduction".to_string()));
+
+    let mut page = Page::new(612.0, 792.0, 1);
+    page.blocks.push(block_from_lines(&[
+        "Abstract—We propose a new",
+        "model for translation.",
+    ]));
+    page.blocks.push(block_from_lines(&["1 Introduction"]));
+    page.blocks.push(block_from_lines(&["Translation is hard."]));
+    let mut pages = vec![page];
+
+    parse_extract_secsions(&mut config, &mut pages).unwrap();
+
+    assert_eq!(pages[0].blocks[0].section, "Abstract");
+    assert_eq!(
+        pages[0].blocks[0].get_text(),
+        "We propose a new model for translation."
+    );
+    assert_eq!(pages[0].blocks[2].section, "Introduction");
+
+    let sections = Section::from_pages(&pages);
+    assert_eq!(sections[0].title, "Abstract");
+    assert_eq!(
+        sections[0].contents,
+        vec!["We propose a new model for translation."]
+    );
+}
+
+#[test]
+fn test_split_inline_abstract() {
+    let mut line = block_from_lines(&["Abstract: We propose"]).lines[0].clone();
+    assert!(split_inline_abstract(&mut line));
+    assert_eq!(line.get_text(), "We propose");
+
+    let mut line = block_from_lines(&["Abstracts are short"]).lines[0].clone();
+    assert!(!split_inline_abstract(&mut line));
+    assert_eq!(line.get_text(), "Abstracts are short");
+}
+
+/// Writes a page image with a ruled 3x3 table whose top edge is at `top`, or a blank page if `top` is `None`.
+fn write_ruled_page(fig_path: &str, top: Option<i32>) {
+    let mut image = Mat::new_rows_cols_with_default(
+        792,
+        612,
+        opencv::core::CV_8UC3,
+        opencv::core::Scalar::all(255.0),
+    )
+    .unwrap();
+    if let Some(top) = top {
+        let black = opencv::core::Scalar::all(0.0);
+        for i in 0..4 {
+            let y = top + 40 * i;
+            imgproc::line(
+                &mut image,
+                opencv::core::Point::new(100, y),
+                opencv::core::Point::new(500, y),
+                black,
+                1,
+                imgproc::LINE_8,
+                0,
+            )
+            .unwrap();
+            let x = 100 + 400 * i / 3;
+            imgproc::line(
+                &mut image,
+                opencv::core::Point::new(x, top),
+                opencv::core::Point::new(x, top + 120),
+                black,
+                1,
+                imgproc::LINE_8,
+                0,
+            )
+            .unwrap();
+        }
+    }
+    imgcodecs::imwrite_def(fig_path, &image).unwrap();
+}
+
+#[test]
+fn test_detect_tables_keeps_page_order() {
+    let mut config = ParserConfig::new();
+    // a table at a different height on each chosen page; page 2 is before `table_start_page`
+    let table_tops = HashMap::from([(2, 100), (5, 150), (12, 300), (17, 450)]);
+    let mut page_sizes = Vec::new();
+    for page_number in 1..=20 {
+        let fig_path = format!("/tmp/rsrpp-detect-tables-{}.png", page_number);
+        write_ruled_page(&fig_path, table_tops.get(&page_number).copied());
+        config.pdf_figures.insert(page_number, fig_path);
+        page_sizes.push((page_number, 612.0, 792.0));
+    }
+
+    let tables = detect_tables(&mut config, &page_sizes, 3);
+
+    assert_eq!(tables.len(), 20);
+    for (index, page_tables) in tables.iter().enumerate() {
+        let page_number = (index + 1) as PageNumber;
+        match table_tops.get(&page_number) {
+            Some(top) if page_number >= 3 => {
+                assert_eq!(page_tables.len(), 1, "page {}", page_number);
+                assert!((page_tables[0].coord.top_left.y - *top as f32).abs() < 5.0);
+            }
+            _ => assert!(page_tables.is_empty(), "page {}", page_number),
+        }
+    }
+    let _ = config.clean_files();
+}
+
+#[test]
+#[ignore]
+fn test_detect_tables_parallel_timing() {
+    let mut config = ParserConfig::new();
+    let mut page_sizes = Vec::new();
+    for page_number in 1..=40 {
+        let fig_path = format!("/tmp/rsrpp-detect-tables-timing-{}.png", page_number);
+        write_ruled_page(&fig_path, Some(100 + 10 * (page_number as i32 % 20)));
+        config.pdf_figures.insert(page_number, fig_path);
+        page_sizes.push((page_number, 612.0, 792.0));
+    }
+
+    let time = std::time::Instant::now();
+    let sequential = page_sizes
+        .iter()
+        .map(|(page_number, width, height)| {
+            let mut tables = Vec::new();
+            let fig_path = config.pdf_figures.get(page_number).unwrap();
+            extract_tables(fig_path, &mut tables, *width as i32, *height as i32).unwrap();
+            tables.retain(|table| table.confidence >= config.min_table_confidence);
+            tables
+        })
+        .collect::<Vec<Vec<DetectedTable>>>();
+    let sequential_time = time.elapsed();
+
+    let time = std::time::Instant::now();
+    let parallel = detect_tables(&mut config, &page_sizes, 1);
+    let parallel_time = time.elapsed();
+
+    println!(
+        "sequential: {:?}, parallel: {:?}",
+        sequential_time, parallel_time
+    );
+    assert_eq!(parallel, sequential);
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_section_title_at_bottom_of_column() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Introduction".to_string()));
+    config.sections.push((2, "Method".to_string()));
+
+    let mut first = Page::new(612.0, 792.0, 1);
+    first.blocks.push(block_at(50.0, 50.0, 250.0, &["1 Introduction"]));
+    first.blocks.push(block_at(
+        50.0,
+        80.0,
+        250.0,
+        &["Translation", "is", "hard.", "Really."],
+    ));
+    first.blocks.push(block_at(320.0, 50.0, 250.0, &["We", "fix", "it", "here."]));
+
+    // the heading closes the left column, its body continues at the top of the right column
+    let mut second = Page::new(612.0, 792.0, 2);
+    second.blocks.push(block_at(
+        320.0,
+        50.0,
+        250.0,
+        &["Our", "model", "uses", "attention."],
+    ));
+    second.blocks.push(block_at(
+        50.0,
+        50.0,
+        250.0,
+        &["More", "about", "it", "here."],
+    ));
+    second.blocks.push(block_at(50.0, 700.0, 30.0, &["2 Method"]));
+    let mut pages = vec![first, second];
+
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[1].blocks.len(), 3);
+
+    adjst_columns(&mut pages, &config);
+    parse_extract_secsions(&mut config, &mut pages).unwrap();
+
+    let texts = pages[1]
+        .blocks
+        .iter()
+        .map(|block| (block.get_text(), block.section.clone()))
+        .collect::<Vec<(String, String)>>();
+    assert_eq!(
+        texts,
+        vec![
+            (
+                "More about it here.".to_string(),
+                "Introduction".to_string()
+            ),
+            ("2 Method".to_string(), "Method".to_string()),
+            (
+                "Our model uses attention.".to_string(),
+                "Method".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_trigram_similarity() {
+    assert_eq!(
+        normalize_for_matching("  Attention-Is All\nYou  Need. "),
+        "attention is all you need"
+    );
+    assert_eq!(trigram_similarity("Vaswani et al.", "vaswani et al"), 1.0);
+    assert_eq!(trigram_similarity("abc", "xyz"), 0.0);
+    assert_eq!(trigram_similarity("ab", "ab"), 1.0);
+    assert_eq!(trigram_similarity("", ""), 1.0);
+    assert_eq!(trigram_similarity("", "abc"), 0.0);
+    let similarity = trigram_similarity(
+        "Attention is all you need. In NeurIPS, 2017.",
+        "A. Vaswani et al. Attention is all you need. NeurIPS 2017",
+    );
+    assert!(0.5 < similarity && similarity < 1.0);
+}
+
+#[test]
+fn test_parse_extract_textarea_block_thresholds() {
+    let build_pages = || {
+        let mut page = Page::new(612.0, 792.0, 2);
+        page.blocks.push(block_at(
+            50.0,
+            50.0,
+            500.0,
+            &["Our model", "is", "better", "than", "before."],
+        ));
+        page.blocks.push(block_at(50.0, 120.0, 100.0, &["BLEU 28.4"]));
+        page.blocks.push(block_at(
+            50.0,
+            200.0,
+            500.0,
+            &["It", "also", "trains", "faster", "now."],
+        ));
+        vec![page]
+    };
+
+    let mut config = ParserConfig::new();
+    let mut pages = build_pages();
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks.len(), 2);
+
+    config.min_block_width_ratio = 0.1;
+    let mut pages = build_pages();
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks.len(), 3);
+    assert_eq!(pages[0].blocks[1].get_text(), "BLEU 28.4");
+
+    config.min_block_width_ratio = 0.3;
+    config.min_block_lines = 1;
+    let mut pages = build_pages();
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+    assert_eq!(pages[0].blocks.len(), 3);
+}
+
+#[test]
+fn test_extract_email_domains() {
+    let mut first = Page::new(612.0, 792.0, 1);
+    first.blocks.push(block_from_lines(&["Attention Is All You Need"]));
+    first.blocks.push(block_from_lines(&[
+        "{avaswani, noam, nikip}@google.com",
+        "aidan@cs.toronto.edu",
+        "usz@Google.com",
+    ]));
+    let mut second = Page::new(612.0, 792.0, 2);
+    second.blocks.push(block_from_lines(&["contact: someone@example.org"]));
+
+    let pages = vec![first, second];
+    assert_eq!(
+        extract_email_domains(&pages),
+        vec!["google.com", "cs.toronto.edu"]
+    );
+    assert_eq!(
+        PaperMetadata::from_pages(&pages).email_domains,
+        vec!["google.com", "cs.toronto.edu"]
+    );
+}
+
+#[test]
+fn test_detect_citation_style() {
+    let section = |title: &str, contents: &[&str]| Section {
+        title: title.to_string(),
+        contents: contents.iter().map(|content| content.to_string()).collect(),
+        ..Default::default()
+    };
+
+    let numeric = vec![
+        section(
+            "Introduction",
+            &["Attention [1] replaced recurrence [2, 3] in translation [4-6]."],
+        ),
+        section(
+            "References",
+            &[
+                "[1] A. Vaswani et al. Attention is all you need. 2017.",
+                "[2] K. He et al. Deep residual learning. 2016.",
+            ],
+        ),
+    ];
+    assert_eq!(detect_citation_style(&numeric), CitationStyle::Numeric);
+
+    let author_year = vec![
+        section(
+            "Introduction",
+            &["Attention (Vaswani et al., 2017) replaced recurrence (Sutskever and Le, 2014). As shown by Bahdanau et al. (2015), alignment helps."],
+        ),
+        section(
+            "References",
+            &["Vaswani, A., et al. (2017). Attention is all you need."],
+        ),
+    ];
+    assert_eq!(
+        detect_citation_style(&author_year),
+        CitationStyle::AuthorYear
+    );
+
+    let unknown = vec![section("Introduction", &["No citations here."])];
+    assert_eq!(detect_citation_style(&unknown), CitationStyle::Unknown);
+}
+
+#[test]
+fn test_rewrite_url() {
+    let mut config = ParserConfig::new();
+    assert_eq!(
+        config.rewrite_url("https://arxiv.org/pdf/1706.03762"),
+        "https://arxiv.org/pdf/1706.03762"
+    );
+
+    config.use_arxiv_mirror = true;
+    assert_eq!(
+        config.rewrite_url("https://arxiv.org/pdf/1706.03762"),
+        "https://export.arxiv.org/pdf/1706.03762"
+    );
+
+    config.url_rewrites.push((
+        "https://arxiv.org/".to_string(),
+        "https://proxy.example.edu/arxiv/".to_string(),
+    ));
+    assert_eq!(
+        config.rewrite_url("https://arxiv.org/pdf/1706.03762"),
+        "https://proxy.example.edu/arxiv/pdf/1706.03762"
+    );
+    assert_eq!(
+        config.rewrite_url("https://openreview.net/pdf?id=abc"),
+        "https://openreview.net/pdf?id=abc"
+    );
+}
+
+#[test]
+fn test_parse_html2pages_zero_width_word() {
+    let mut config = ParserConfig::new();
+    let html = scraper::Html::parse_document(
+        r#"<html><body><doc><page width="612" height="792">
+        <flow><block xMin="50" yMin="50" xMax="200" yMax="62">
+        <line xMin="50" yMin="50" xMax="200" yMax="62">
+        <word xMin="50" yMin="50" xMax="90" yMax="62">Hello</word>
+        <word xMin="95" yMin="50" xMax="95" yMax="62">,</word>
+        <word xMin="100" yMin="50" xMax="200" yMax="62">world</word>
+        </line></block></flow></page></doc></body></html>"#,
+    );
+
+    let pages = parse_html2pages_with_tables_from(&mut config, html, 2).unwrap();
+
+    let line = &pages[0].blocks[0].lines[0];
+    assert_eq!(line.get_text(), "Hello , world");
+    assert!(line.words.iter().all(|word| word.width > 0.0 && word.height > 0.0));
+    let word = &line.words[1];
+    let coordinate = Coordinate::from_object(word.x, word.y, word.width, word.height);
+    assert!(coordinate.is_contained_in(&Coordinate::from_object(
+        line.x,
+        line.y,
+        line.width,
+        line.height
+    )));
+}
+
+#[test]
+fn test_parse_remove_footers() {
+    let mut config = ParserConfig::new();
+    config.remove_footers = true;
+
+    let mut page = Page::new(612.0, 792.0, 9);
+    page.blocks.push(block_at(
+        50.0,
+        600.0,
+        500.0,
+        &["In conclusion, attention is all you need."],
+    ));
+    page.blocks.push(block_at(
+        50.0,
+        740.0,
+        500.0,
+        &["35th Conference on Neural Information Processing Systems (NeurIPS 2021)."],
+    ));
+    page.blocks.push(block_at(
+        50.0,
+        100.0,
+        500.0,
+        &["Preprint. is mentioned mid-page"],
+    ));
+    let mut pages = vec![page];
+
+    parse_remove_footers(&config, &mut pages).unwrap();
+
+    let texts = pages[0].blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+    assert_eq!(
+        texts,
+        vec![
+            "In conclusion, attention is all you need.",
+            "Preprint. is mentioned mid-page"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_parse_raw() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let raw_pages = parse_raw(url, &mut config).await.unwrap();
+    let _ = config.clean_files();
+
+    let mut config = ParserConfig::new();
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let _ = config.clean_files();
+
+    let raw_blocks = raw_pages.iter().map(|page| page.blocks.len()).sum::<usize>();
+    let blocks = pages.iter().map(|page| page.blocks.len()).sum::<usize>();
+    assert_eq!(raw_pages.len(), pages.len());
+    assert!(raw_blocks >= blocks);
+    assert!(raw_pages
+        .iter()
+        .flat_map(|page| page.blocks.iter())
+        .all(|block| block.section.is_empty()));
+}
+
+#[tokio::test]
+async fn test_page_stream_matches_eager_pages() {
+    let mut config = ParserConfig::new();
+    let html = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">
+<html xmlns="http://www.w3.org/1999/xhtml"><head><title></title>
+<meta name="Producer" content="pdfTeX"/>
+</head><body><doc><page width="612.000000" height="792.000000">
+<flow><block xMin="50" yMin="50" xMax="200" yMax="62">
+<line xMin="50" yMin="50" xMax="200" yMax="62">
+<word xMin="50" yMin="50" xMax="90" yMax="62">R&amp;D</word>
+<word xMin="95" yMin="50" xMax="95" yMax="62">,</word>
+<word xMin="100" yMin="50" xMax="200" yMax="62">a&lt;b</word>
+</line>
+<line xMin="50" yMin="64" xMax="200" yMax="76">
+<word xMin="50" yMin="64" xMax="200" yMax="76">Attention-</word>
+</line></block></flow></page>
+<page width="612.000000" height="792.000000">
+</page>
+<page width="612.000000" height="792.000000">
+<flow><block xMin="50" yMin="60" xMax="200" yMax="72">
+<line xMin="50" yMin="60" xMax="200" yMax="72">
+<word xMin="50" yMin="60" xMax="60" yMax="72">1</word>
+<word xMin="65" yMin="60" xMax="200" yMax="72">Introduction</word>
+</line></block>
+<block xMin="50" yMin="80" xMax="200" yMax="92">
+<line xMin="50" yMin="80" xMax="200" yMax="92">
+<word xMin="50" yMin="80" xMax="200" yMax="92">Transformer</word>
+</line></block></flow></page>
+</doc></body></html>"#;
+    std::fs::write(&config.pdf_text_path, html).unwrap();
+    config.sections = vec![(3, "Introduction".to_string())];
+    config.keep_intermediate_files = false;
+
+    let stream = PageStream {
+        reader: Some(PageReader::open(config.clone(), 1000).unwrap()),
+        pending: None,
+        diagnostics: Vec::new(),
+    };
+    let streamed = stream.collect::<Vec<Result<Page, ParseError>>>().await;
+    let streamed = streamed.into_iter().collect::<Result<Vec<Page>, ParseError>>().unwrap();
+    // the intermediate files are removed once the stream is exhausted
+    assert!(!Path::new(&config.pdf_text_path).exists());
+
+    let mut eager =
+        parse_html2pages_with_tables_from(&mut config, scraper::Html::parse_document(html), 1000)
+            .unwrap();
+    parse_extract_secsions(&mut config, &mut eager).unwrap();
+    parse_classify_blocks(&mut config, &mut eager).unwrap();
+    parse_associate_captions(&config, &mut eager).unwrap();
+
+    assert_eq!(streamed.len(), 2);
+    assert_eq!(streamed[0].blocks[0].lines[0].get_text(), "R&D , a<b");
+    assert_eq!(streamed[0].blocks[0].section, "Abstract");
+    assert_eq!(streamed[1].page_nubmer, 3);
+    assert_eq!(streamed[1].blocks[1].section, "Introduction");
+    assert_eq!(streamed[1].blocks[0].block_type, BlockType::Header);
+    assert_eq!(streamed[1].blocks[1].block_type, BlockType::Body);
+    assert_eq!(streamed, eager);
+}
+
+#[test]
+fn test_section_numbers_order_sections() {
+    let mut page = Page::new(612.0, 792.0, 1);
+    for (text, section) in [
+        ("We propose a model.", "Abstract"),
+        ("Experiments", "Experiments"),
+        ("1 Introduction", "Introduction"),
+        ("Translation is hard.", "Introduction"),
+        ("2. Method", "Method"),
+        ("We use attention.", "Method"),
+        ("3 Experiments", "Experiments"),
+        ("It works.", "Experiments"),
+        ("3.1 Setup", "Setup"),
+        ("We train on GPUs.", "Setup"),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&vec![page]);
+    let order = sections
+        .iter()
+        .map(|section| {
+            (
+                section.index,
+                section.title.as_str(),
+                section.number.clone(),
+            )
+        })
+        .collect::<Vec<(usize, &str, Option<String>)>>();
+    assert_eq!(
+        order,
+        vec![
+            (0, "Abstract", None),
+            (1, "Introduction", Some("1".to_string())),
+            (2, "Method", Some("2".to_string())),
+            (3, "Experiments", Some("3".to_string())),
+            (4, "Setup", Some("3.1".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_numbers() {
+    let section = Section {
+        title: "Results".to_string(),
+        contents: vec![
+            "In 2017, our model achieves 92.3% accuracy on Table 2 and an F1 of 0.87.".to_string(),
+            "The improvement is significant (p < 0.05) and inference takes 120 ms.".to_string(),
+        ],
+        ..Default::default()
+    };
+
+    let mentions = extract_numbers(&section);
+    let values = mentions
+        .iter()
+        .map(|mention| (mention.value, mention.unit.clone()))
+        .collect::<Vec<(f64, Option<String>)>>();
+    assert_eq!(
+        values,
+        vec![
+            (92.3, Some("%".to_string())),
+            (0.87, None),
+            (0.05, None),
+            (120.0, Some("ms".to_string())),
+        ]
+    );
+    assert!(mentions[0].context.contains("achieves 92.3% accuracy"));
+    assert!(mentions[2].context.contains("p < 0.05"));
+}
+
+fn detected_table(x1: f32, y1: f32, x2: f32, y2: f32) -> DetectedTable {
+    return DetectedTable::new(Coordinate::from_rect(x1, y1, x2, y2), 1.0);
+}
+
+#[test]
+fn test_table_confidence() {
+    let ruling = |y: f32, x1: f32, x2: f32| (Point::new(x1, y), Point::new(x2, y));
+    // a ruled table with many full-width lines
+    let strong = (0..8).map(|i| ruling(100.0 + 20.0 * i as f32, 50.0, 550.0)).collect::<Vec<_>>();
+    let strong_coord = Coordinate::from_rect(50.0, 100.0, 550.0, 240.0);
+    // three short lines close together, e.g. underlines or a double rule
+    let faint = (0..3).map(|i| ruling(600.0 + 2.0 * i as f32, 200.0, 360.0)).collect::<Vec<_>>();
+    let faint_coord = Coordinate::from_rect(200.0, 600.0, 360.0, 604.0);
+
+    let strong_confidence = table_confidence(&strong, &strong_coord, 612.0);
+    let faint_confidence = table_confidence(&faint, &faint_coord, 612.0);
+    assert!(strong_confidence > faint_confidence);
+    assert!(strong_confidence <= 1.0 && faint_confidence >= 0.0);
+    assert_eq!(strong_confidence, 1.0);
+    assert_eq!(table_confidence(&[], &faint_coord, 612.0), 0.0);
+}
+
+#[test]
+fn test_dedupe_regions() {
+    let mut page = Page::new(612.0, 792.0, 1);
+    page.tables.push(DetectedTable::new(
+        Coordinate::from_rect(100.0, 100.0, 500.0, 300.0),
+        0.4,
+    ));
+    page.tables.push(DetectedTable::new(
+        Coordinate::from_rect(110.0, 90.0, 505.0, 290.0),
+        0.8,
+    ));
+    page.tables.push(DetectedTable::new(
+        Coordinate::from_rect(100.0, 500.0, 500.0, 700.0),
+        0.5,
+    ));
+
+    dedupe_regions(&mut page);
+
+    assert_eq!(
+        page.tables,
+        vec![
+            DetectedTable::new(Coordinate::from_rect(100.0, 90.0, 505.0, 300.0), 0.8),
+            DetectedTable::new(Coordinate::from_rect(100.0, 500.0, 500.0, 700.0), 0.5),
+        ]
+    );
+}
+
+#[test]
+fn test_paper_from_pages() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    page.tables.push(detected_table(100.0, 500.0, 500.0, 700.0));
+    for (text, section) in [
+        ("We propose a model.", "Abstract"),
+        ("PDFs are everywhere.", "1 Introduction"),
+        ("It works.", "Results"),
+        (
+            "[1] A. Author. A paper. [2] B. Author. Another paper.",
+            "References",
+        ),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let paper = Paper::from_pages(vec![page], &ParserConfig::new());
+    assert_eq!(
+        paper.abstract_text(),
+        Some("We propose a model.".to_string())
+    );
+    assert_eq!(
+        paper.section("introduction").unwrap().title,
+        "1 Introduction"
+    );
+    assert!(paper.section("Conclusion").is_none());
+    assert_eq!(paper.body_text(), "PDFs are everywhere.\n\nIt works.");
+    assert_eq!(
+        paper.references().iter().map(|reference| reference.text.as_str()).collect::<Vec<_>>(),
+        vec!["[1] A. Author. A paper.", "[2] B. Author. Another paper."]
+    );
+    assert_eq!(paper.references()[1].authors, vec!["B. Author"]);
+    assert_eq!(
+        paper.references()[1].title,
+        Some("Another paper".to_string())
+    );
+    assert_eq!(
+        paper.tables(),
+        vec![(1, detected_table(100.0, 500.0, 500.0, 700.0))]
+    );
+}
+
+#[tokio::test]
+async fn test_parse_paper() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let paper = parse_paper(url, &mut config, false).await.unwrap();
+
+    assert!(paper.abstract_text().unwrap().len() > 0);
+    assert!(paper.section("Introduction").is_some());
+    assert!(paper.references().len() > 0);
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_block_looks_like_heading() {
+    let mut heading = block_from_lines(&["Related Work"]);
+    for word in heading.lines[0].words.iter_mut() {
+        word.height = 14.0;
+    }
+    assert!(heading.looks_like_heading(10.0));
+    assert!(block_from_lines(&["3.1 Attention Layers"]).looks_like_heading(10.0));
+    assert!(block_from_lines(&["A.2 Proofs of Lemma 3"]).looks_like_heading(10.0));
+    let mut appendix = block_from_lines(&["A Proofs"]);
+    for word in appendix.lines[0].words.iter_mut() {
+        word.height = 12.0;
+    }
+    assert!(appendix.looks_like_heading(10.0));
+
+    assert!(!block_from_lines(&["Related Work"]).looks_like_heading(10.0));
+    assert!(!block_from_lines(&["A New Approach to Parsing"]).looks_like_heading(10.0));
+    assert!(!block_from_lines(&["We propose a new model."]).looks_like_heading(10.0));
+    assert!(!block_from_lines(&[
+        "Transformers have become the dominant architecture for",
+        "sequence modeling in natural language processing and",
+        "computer vision"
+    ])
+    .looks_like_heading(10.0));
+}
+
+#[test]
+fn test_sections_to_markdown() {
+    let sections = vec![
+        Section {
+            title: "Abstract".to_string(),
+            contents: vec!["We propose the Transformer.".to_string()],
+            level: 1,
+            ..Default::default()
+        },
+        Section {
+            title: "Encoder and Decoder Stacks".to_string(),
+            contents: vec![
+                "The encoder has <math>N = 6</math> layers.".to_string(),
+                "Each layer has two sub-layers.".to_string(),
+            ],
+            captions: vec![Caption {
+                kind: CaptionKind::Figure,
+                label: "1".to_string(),
+                text: "The Transformer.".to_string(),
+            }],
+            level: 2,
+            ..Default::default()
+        },
+        Section {
+            title: "Attention".to_string(),
+            level: 2,
+            elements: vec![
+                SectionElement::Paragraph("Queries and keys.".to_string()),
+                SectionElement::Caption("Figure 2: Attention.".to_string()),
+                SectionElement::Paragraph("Values.".to_string()),
+            ],
+            ..Default::default()
+        },
+    ];
+
+    let expected = include_str!("testdata/sections.md");
+    assert_eq!(sections_to_markdown(&sections), expected);
+}
+
+#[test]
+fn test_section_hierarchy() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (lines, section) in [
+        (vec!["We propose a model."], "Abstract"),
+        (
+            vec!["3 Model Architecture", "Most models have an encoder."],
+            "Model Architecture",
+        ),
+        (
+            vec![
+                "3.1 Encoder and Decoder Stacks",
+                "The encoder has six layers.",
+            ],
+            "Encoder and Decoder Stacks",
+        ),
+        (
+            vec!["3.2 Attention", "An attention function maps queries."],
+            "Attention",
+        ),
+        (
+            vec!["4 Why Self-Attention", "We compare layers."],
+            "Why Self-Attention",
+        ),
+    ] {
+        let mut block = block_from_lines(&lines);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&vec![page]);
+    let levels = sections
+        .iter()
+        .map(|section| {
+            (
+                section.title.as_str(),
+                section.level,
+                section.parent.as_deref(),
+            )
+        })
+        .collect::<Vec<(&str, u8, Option<&str>)>>();
+    assert_eq!(
+        levels,
+        vec![
+            ("Abstract", 1, None),
+            ("Model Architecture", 1, None),
+            ("Encoder and Decoder Stacks", 2, Some("Model Architecture")),
+            ("Attention", 2, Some("Model Architecture")),
+            ("Why Self-Attention", 1, None),
+        ]
+    );
+
+    let tree = SectionTree::from_sections(&sections);
+    let titles = |trees: &Vec<SectionTree>| {
+        trees.iter().map(|tree| tree.section.title.clone()).collect::<Vec<String>>()
+    };
+    assert_eq!(
+        titles(&tree),
+        vec!["Abstract", "Model Architecture", "Why Self-Attention"]
+    );
+    assert_eq!(
+        titles(&tree[1].children),
+        vec!["Encoder and Decoder Stacks", "Attention"]
+    );
+    assert!(tree[0].children.is_empty());
+}
+
+#[test]
+fn test_section_numbers_on_their_own_line() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (lines, section) in [
+        (vec!["We propose a model."], "Abstract"),
+        // the number and the title on separate lines of one block
+        (
+            vec!["3", "Model Architecture", "Most models have an encoder."],
+            "Model Architecture",
+        ),
+        // the number in a block of its own, above the title
+        (vec!["3.1"], "Model Architecture"),
+        (
+            vec!["Encoder and Decoder Stacks", "The encoder has six layers."],
+            "Encoder and Decoder Stacks",
+        ),
+        // extra whitespace in the heading
+        (
+            vec!["4  Why   Self-Attention", "We compare layers."],
+            "Why Self-Attention",
+        ),
+    ] {
+        let mut block = block_from_lines(&lines);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+
+    let sections = Section::from_pages(&vec![page]);
+    let numbers = sections
+        .iter()
+        .map(|section| {
+            (
+                section.title.as_str(),
+                section.number.as_deref(),
+                section.level,
+                section.parent.as_deref(),
+            )
+        })
+        .collect::<Vec<(&str, Option<&str>, u8, Option<&str>)>>();
+    assert_eq!(
+        numbers,
+        vec![
+            ("Abstract", None, 1, None),
+            ("Model Architecture", Some("3"), 1, None),
+            (
+                "Encoder and Decoder Stacks",
+                Some("3.1"),
+                2,
+                Some("Model Architecture")
+            ),
+            ("Why Self-Attention", Some("4"), 1, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_section_hierarchy_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages(&pages);
+
+    let architecture =
+        sections.iter().find(|section| section.title == "Model Architecture").unwrap();
+    assert_eq!(architecture.level, 1);
+    let stacks =
+        sections.iter().find(|section| section.title == "Encoder and Decoder Stacks").unwrap();
+    assert_eq!(stacks.level, 2);
+    assert_eq!(stacks.parent.as_deref(), Some("Model Architecture"));
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_section_elements_keep_reading_order() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (text, block_type) in [
+        ("We propose a model.", BlockType::Body),
+        ("Figure 1: The model.", BlockType::Caption),
+        ("It works well.", BlockType::Body),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = "Introduction".to_string();
+        block.block_type = block_type;
+        page.blocks.push(block);
+    }
+    let pages = vec![page];
+
+    let sections = Section::from_pages(&pages);
+    assert!(sections[0].elements.is_empty());
+
+    let mut config = ParserConfig::new();
+    config.include_elements = true;
+    let sections = Section::from_pages_with_config(&pages, &config);
+    assert_eq!(
+        sections[0].contents,
+        vec!["We propose a model.", "It works well."]
+    );
+    assert_eq!(sections[0].captions.len(), 1);
+    assert_eq!(
+        sections[0].elements,
+        vec![
+            SectionElement::Paragraph("We propose a model.".to_string()),
+            SectionElement::Caption("Figure 1: The model.".to_string()),
+            SectionElement::Paragraph("It works well.".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_keywords() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (lines, section) in [
+        (
+            vec![
+                "We study parsing.",
+                "Keywords: PDF parsing; layout analysis.",
+            ],
+            "Abstract",
+        ),
+        (vec!["PDFs are everywhere."], "Introduction"),
+    ] {
+        let mut block = block_from_lines(&lines);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+    let pages = vec![page];
+
+    let metadata = PaperMetadata::from_pages(&pages);
+    assert_eq!(metadata.keywords, vec!["PDF parsing", "layout analysis"]);
+
+    let sections = Section::from_pages(&pages);
+    assert_eq!(sections[0].title, "Abstract");
+    assert_eq!(sections[0].contents, vec!["We study parsing."]);
+    assert_eq!(sections[1].contents, vec!["PDFs are everywhere."]);
+
+    assert_eq!(
+        parse_keywords("Index Terms\u{2014}deep learning, vision"),
+        vec!["deep learning", "vision"]
+    );
+    assert_eq!(
+        block_from_lines(&["Key words retrieval"]).split_keywords().unwrap().0.lines.len(),
+        0
+    );
+}
+
+#[test]
+fn test_keywords_outside_abstract() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&["Keywords such as these are indexed, ranked, and stored."]);
+    block.section = "Introduction".to_string();
+    page.blocks.push(block);
+
+    assert!(extract_keywords(&vec![page]).is_empty());
+}
+
+#[test]
+fn test_sections_after_and_between() {
+    let sections = [
+        "Results",
+        "Abstract",
+        "1 Introduction",
+        "Method",
+        "Appendix A",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, title)| Section {
+        index: (i + 4) % 5,
+        title: title.to_string(),
+        ..Default::default()
+    })
+    .collect::<Vec<Section>>();
+    let titles = |sections: Vec<Section>| {
+        sections.into_iter().map(|section| section.title).collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        titles(sections_after(&sections, "Method")),
+        vec!["Method", "Appendix A", "Results"]
+    );
+    assert_eq!(
+        titles(sections_after(&sections, "Conclusion")),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        titles(sections_between(&sections, "introduction", "results")),
+        vec!["1 Introduction", "Method", "Appendix A"]
+    );
+    assert_eq!(
+        titles(sections_between(&sections, "Intro", "Conclusion")),
+        vec!["1 Introduction", "Method", "Appendix A", "Results"]
+    );
+}
+
+#[test]
+fn test_try_pages2json() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&["We propose a model."]);
+    block.section = "Introduction".to_string();
+    page.blocks.push(block);
+    let pages = vec![page];
+
+    let json = try_pages2json(&pages).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["title"], "Introduction");
+    assert_eq!(value[0]["contents"], "We propose a model.");
+    let value2: serde_json::Value = serde_json::from_str(&pages2json(&pages)).unwrap();
+    assert_eq!(value2, value);
+}
+
+#[test]
+fn test_remove_repeated_regions() {
+    let logo = detected_table(40.0, 20.0, 140.0, 60.0);
+    let table = detected_table(100.0, 300.0, 500.0, 500.0);
+    let mut tables = vec![
+        vec![logo.clone(), table.clone()],
+        vec![detected_table(41.0, 20.0, 141.0, 61.0)],
+        vec![logo.clone()],
+        vec![logo.clone(), detected_table(100.0, 100.0, 500.0, 250.0)],
+        vec![],
+    ];
+
+    remove_repeated_regions(&mut tables);
+
+    assert_eq!(
+        tables,
+        vec![
+            vec![table],
+            vec![],
+            vec![],
+            vec![detected_table(100.0, 100.0, 500.0, 250.0)],
+            vec![],
+        ]
+    );
+
+    // a region shared by only two pages is kept
+    let mut tables = vec![vec![logo.clone()], vec![logo.clone()], vec![], vec![]];
+    remove_repeated_regions(&mut tables);
+    assert_eq!(tables[0], vec![logo.clone()]);
+    assert_eq!(tables[1], vec![logo]);
+}
+
+#[tokio::test]
+async fn test_parse_with_xml() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let (pages, xml) = parse_with_xml(url, &mut config, false).await.unwrap();
+
+    assert!(pages.len() > 0);
+    assert!(xml.contains("<pdf2xml"));
+    assert!(xml.contains(r#"<page number="1""#));
+    assert!(Path::new(&config.pdf_xml_path).exists());
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_section_detector() {
+    let detector = SectionDetector::new(|xml: &str| {
+        let heading_regex = regex::Regex::new(r#"<text[^>]*><b>CHAPTER (\w+)</b></text>"#).unwrap();
+        heading_regex.captures_iter(xml).map(|caps| (1, caps[1].to_string())).collect()
+    });
+    let xml = r#"<pdf2xml><page number="1"><text top="10" left="10" width="80" height="12" font="0"><b>CHAPTER Overview</b></text></page></pdf2xml>"#;
+    assert_eq!(detector.detect(xml), vec![(1, "Overview".to_string())]);
+
+    let mut config = ParserConfig::new();
+    config.section_detector = Some(detector.clone());
+    let cloned = config.clone();
+    assert_eq!(cloned.section_detector, Some(detector));
+    assert_ne!(
+        cloned.section_detector,
+        Some(SectionDetector::new(|_: &str| Vec::new()))
+    );
+    assert!(format!("{:?}", config).contains("SectionDetector(..)"));
+}
+
+#[test]
+fn test_parse_extract_textarea_without_sections() {
+    let mut config = ParserConfig::new();
+
+    let mut page = Page::new(612.0, 792.0, 2);
+    page.blocks.push(block_at(
+        50.0,
+        50.0,
+        500.0,
+        &["Body", "text", "of", "the", "paper."],
+    ));
+    let mut heading = block_at(50.0, 120.0, 60.0, &["Overview"]);
+    heading.lines[0].words[0].height = 14.0;
+    page.blocks.push(heading);
+    page.blocks.push(block_at(50.0, 150.0, 60.0, &["(a)"]));
+    page.blocks.push(block_at(
+        50.0,
+        200.0,
+        500.0,
+        &["More", "body", "text", "here."],
+    ));
+    let mut pages = vec![page];
+
+    parse_extract_textarea(&mut config, &mut pages).unwrap();
+    let texts = pages[0].blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+    assert_eq!(
+        texts,
+        vec![
+            "Body text of the paper.",
+            "Overview",
+            "More body text here."
+        ]
+    );
+
+    // no detected sections must not make the column adjustment panic
+    adjst_columns(&mut pages, &config);
+    assert_eq!(pages[0].blocks.len(), 3);
+}
+
+#[test]
+fn test_line_min_confidence() {
+    let mut line = block_from_lines(&["scanned paper text"]).lines[0].clone();
+    assert_eq!(line.min_confidence(), None);
+    let json = serde_json::to_string(&line).unwrap();
+    assert!(!json.contains("confidence"));
+
+    line.words[0].confidence = Some(0.93);
+    line.words[1].confidence = Some(0.41);
+    assert_eq!(line.min_confidence(), Some(0.41));
+    let json = serde_json::to_string(&line).unwrap();
+    assert!(json.contains(r#""confidence":0.41"#));
+}
+
+#[test]
+fn test_section_contains() {
+    let section = Section {
+        title: "Introduction".to_string(),
+        contents: vec!["We study data driven  methods for Large-Scale training.".to_string()],
+        ..Default::default()
+    };
+    assert!(section.contains("data-driven"));
+    assert!(section.contains("Data Driven"));
+    assert!(section.contains("large scale"));
+    assert!(section.contains("largescale"));
+    assert!(!section.contains("model-driven"));
+}
+
+#[test]
+fn test_sections_to_tei() {
+    let section = |title: &str, number: Option<&str>, contents: &[&str]| Section {
+        title: title.to_string(),
+        number: number.map(|number| number.to_string()),
+        contents: contents.iter().map(|content| content.to_string()).collect(),
+        ..Default::default()
+    };
+    let sections = vec![
+        section("Abstract", None, &["The dominant models use RNNs."]),
+        section("Introduction", Some("1"), &["RNNs & LSTMs <dominate>."]),
+        section(
+            "References",
+            None,
+            &[
+                "[1] J. Ba et al. Layer normalization. 2016. [2] D. Bahdanau et al.",
+                "Neural machine translation. 2014.",
+            ],
+        ),
+    ];
+    let paper = Paper {
+        metadata: PaperMetadata {
+            title: Some("Attention Is All You Need".to_string()),
+            authors: vec!["Ashish Vaswani".to_string(), "Noam Shazeer".to_string()],
+            email_domains: vec!["google.com".to_string()],
+            keywords: Vec::new(),
+        },
+        references: parse_references_heuristic(&Section::references_raw(&sections).join(" ")),
+        sections: sections,
+        pages: Vec::new(),
+    };
+
+    let tei = sections_to_tei(&paper);
+
+    assert!(tei.starts_with("<?xml"));
+    assert!(tei.contains(r#"<title level="a" type="main">Attention Is All You Need</title>"#));
+    assert!(tei.contains("<author><persName>Noam Shazeer</persName></author>"));
+    assert!(tei.contains("<abstract>\n        <p>The dominant models use RNNs.</p>"));
+    assert!(tei.contains(r#"<head n="1">Introduction</head>"#));
+    assert!(tei.contains("<p>RNNs &amp; LSTMs &lt;dominate&gt;.</p>"));
+    assert!(!tei.contains("<head>References</head>"));
+    assert!(tei.contains(
+        r#"<biblStruct><note type="raw_reference">[1] J. Ba et al. Layer normalization. 2016.</note></biblStruct>"#
+    ));
+    assert!(tei.contains(
+        r#"<note type="raw_reference">[2] D. Bahdanau et al. Neural machine translation. 2014.</note>"#
+    ));
+    // the TEI output has the same entries as the JSON output
+    assert_eq!(tei.matches("raw_reference").count(), paper.references.len());
+    assert_eq!(paper.references.len(), 2);
+    assert!(tei.trim_end().ends_with("</TEI>"));
+}
+
+#[test]
+fn test_parse_validate_sections() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "Introduction".to_string()));
+    config.sections.push((1, "Related Work".to_string()));
+    config.sections.push((2, "Method".to_string()));
+
+    let mut page = Page::new(612.0, 792.0, 1);
+    for section in ["Abstract", "Introduction", "Method"] {
+        let mut block = block_from_lines(&["Some text."]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+    let pages = vec![page];
+
+    parse_validate_sections(&mut config, &pages).unwrap();
+
+    assert_eq!(
+        config.sections,
+        vec![(1, "Introduction".to_string()), (2, "Method".to_string())]
+    );
+    assert_eq!(
+        config.diagnostics,
+        vec![ParseDiagnostic::PhantomSection {
+            title: "Related Work".to_string(),
+            page: 1
+        }]
+    );
+}
+
+#[test]
+fn test_parse_extract_secsions_numbered_headings() {
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "3 Method".to_string()));
+    config.sections.push((1, "Experiments".to_string()));
+
+    let mut page = Page::new(612.0, 792.0, 1);
+    page.blocks.push(block_from_lines(&["3", "Method"]));
+    page.blocks.push(block_from_lines(&["We use attention."]));
+    page.blocks.push(block_from_lines(&["4.  Experiments"]));
+    page.blocks.push(block_from_lines(&["It works."]));
+    let mut pages = vec![page];
+
+    parse_extract_secsions(&mut config, &mut pages).unwrap();
+    let sections =
+        pages[0].blocks.iter().map(|block| block.section.as_str()).collect::<Vec<&str>>();
+    assert_eq!(
+        sections,
+        vec!["Method", "Method", "Experiments", "Experiments"]
+    );
+
+    parse_validate_sections(&mut config, &pages).unwrap();
+    assert_eq!(config.sections.len(), 2);
+    assert!(config.diagnostics.is_empty());
+}
+
+#[test]
+fn test_write_json() {
+    let mut page = Page::new(612.0, 792.0, 1);
+    let mut block = block_from_lines(&["We propose a model."]);
+    block.section = "Abstract".to_string();
+    page.blocks.push(block);
+    let pages = vec![page];
+    let sections = Section::from_pages(&pages);
+
+    let mut buffer = Vec::new();
+    write_sections_json(&sections, &mut buffer).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        serde_json::to_string(&sections).unwrap()
+    );
+
+    let mut buffer = Vec::new();
+    write_pages_json(&pages, &mut buffer).unwrap();
+    let restored: Vec<Page> = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(restored, pages);
+}
+
+#[test]
+fn test_recover_title() {
+    let mut page = Page::new(612.0, 792.0, 1);
+    let mut stamp = block_at(
+        10.0,
+        200.0,
+        20.0,
+        &["arXiv:1706.03762v7 [cs.CL] 2 Aug 2023"],
+    );
+    stamp.lines[0].height = 300.0;
+    stamp.lines[0].words.iter_mut().for_each(|word| word.height = 20.0);
+    page.blocks.push(stamp);
+    let mut title = block_at(150.0, 100.0, 300.0, &["Attention Is All", "You Need"]);
+    title
+        .lines
+        .iter_mut()
+        .flat_map(|line| line.words.iter_mut())
+        .for_each(|word| word.height = 17.0);
+    page.blocks.push(title);
+    page.blocks.push(block_at(
+        100.0,
+        160.0,
+        400.0,
+        &["Ashish Vaswani Noam Shazeer"],
+    ));
+    let pages = vec![page];
+
+    assert_eq!(
+        recover_title(&pages),
+        Some("Attention Is All You Need".to_string())
+    );
+
+    let mut config = ParserConfig::new();
+    config.pdf_info.insert("title".to_string(), "paper_final_v3".to_string());
+    assert_eq!(
+        PaperMetadata::from_pages_with_config(&vec![], &config).title,
+        None
+    );
+    config.pdf_info.insert("title".to_string(), "Attention Is All You Need".to_string());
+    assert_eq!(
+        PaperMetadata::from_pages_with_config(&vec![], &config).title,
+        Some("Attention Is All You Need".to_string())
+    );
+    // a plausible but different pdfinfo title loses to the recovered title
+    config.pdf_info.insert(
+        "title".to_string(),
+        "Attention Is All You Need (v7)".to_string(),
+    );
+    assert_eq!(
+        PaperMetadata::from_pages_with_config(&pages, &config).title,
+        Some("Attention Is All You Need".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_recover_title_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    assert_eq!(
+        recover_title(&pages),
+        Some("Attention Is All You Need".to_string())
+    );
+    let _ = config.clean_files();
+}
+
+fn table_line(y: f32, cells: &[(f32, &str)]) -> Line {
+    let mut line = Line::new(cells[0].0, y, 0.0, 10.0);
+    for (x, text) in cells {
+        let mut x = *x;
+        for word in text.split_whitespace() {
+            let width = 6.0 * word.len() as f32;
+            line.add_word(word.to_string(), x, y, width, 10.0);
+            x += width + 3.0;
+        }
+    }
+    line.width = line.words.last().map_or(0.0, |word| word.x + word.width) - line.x;
+    return line;
+}
+
+#[test]
+fn test_table_rows() {
+    let lines = vec![
+        table_line(100.0, &[(100.0, "Model"), (250.0, "BLEU"), (350.0, "Cost")]),
+        // pdftotext may split a row into one line per cell
+        table_line(121.0, &[(100.0, "Transformer base")]),
+        table_line(120.0, &[(250.0, "27.3"), (350.0, "3.3")]),
+        // a missing cell
+        table_line(140.0, &[(100.0, "ByteNet"), (250.0, "23.75")]),
+    ];
+
+    let rows = table_rows(&lines);
+    assert_eq!(
+        rows,
+        vec![
+            vec!["Model", "BLEU", "Cost"],
+            vec!["Transformer base", "27.3", "3.3"],
+            vec!["ByteNet", "23.75", ""],
+        ]
+    );
+    assert!(table_rows(&Vec::new()).is_empty());
+}
+
+#[test]
+fn test_section_tables() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut results = block_from_lines(&["We compare the models in Table 1."]);
+    results.y = 100.0;
+    results.section = "Results".to_string();
+    page.blocks.push(results);
+    let mut table = detected_table(100.0, 150.0, 400.0, 250.0);
+    table.rows = vec![vec!["Model".to_string(), "BLEU".to_string()]];
+    page.tables.push(table.clone());
+    // a region without text, e.g. a ruled figure, is not a table of the section
+    page.tables.push(detected_table(100.0, 300.0, 400.0, 400.0));
+    let mut conclusion = block_from_lines(&["Transformers work well."]);
+    conclusion.y = 500.0;
+    conclusion.section = "Conclusion".to_string();
+    page.blocks.push(conclusion);
+
+    let sections = Section::from_pages(&vec![page]);
+    let results = sections.iter().find(|section| section.title == "Results").unwrap();
+    assert_eq!(results.tables, vec![table.to_table()]);
+    let conclusion = sections.iter().find(|section| section.title == "Conclusion").unwrap();
+    assert!(conclusion.tables.is_empty());
+}
+
+#[tokio::test]
+async fn test_table_rows_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+
+    // Table 1 compares layer types by complexity and path length
+    let header = pages
+        .iter()
+        .flat_map(|page| page.tables.iter())
+        .flat_map(|table| table.rows.iter())
+        .find(|row| row.iter().any(|cell| cell.contains("Layer Type")));
+    assert!(header.is_some());
+    assert!(header.unwrap().join(" ").contains("Complexity per Layer"));
+
+    let _ = config.clean_files();
+}
+
+fn figure_page() -> Page {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut intro = block_from_lines(&["We propose a model.", "It is shown below."]);
+    intro.x = 50.0;
+    intro.y = 100.0;
+    intro.width = 500.0;
+    page.blocks.push(intro);
+    // an axis label inside the plot does not end the figure
+    let mut label = block_from_lines(&["accuracy"]);
+    label.x = 60.0;
+    label.y = 200.0;
+    label.width = 40.0;
+    page.blocks.push(label);
+    let mut caption = block_from_lines(&["Figure 1: The model."]);
+    caption.x = 200.0;
+    caption.y = 400.0;
+    caption.width = 200.0;
+    caption.block_type = BlockType::Caption;
+    page.blocks.push(caption);
+    // a table caption has no figure above it
+    let mut table_caption = block_from_lines(&["Table 1: Results."]);
+    table_caption.x = 200.0;
+    table_caption.y = 600.0;
+    table_caption.width = 200.0;
+    table_caption.block_type = BlockType::Caption;
+    page.blocks.push(table_caption);
+    return page;
+}
+
+#[test]
+fn test_figure_regions() {
+    let page = figure_page();
+    assert_eq!(
+        page.figure_regions(),
+        vec![Coordinate::from_rect(50.0, 120.0, 550.0, 400.0)]
+    );
+
+    // in two columns, the region spans the caption's column
+    let mut page = figure_page();
+    page.number_of_columns = 2;
+    page.blocks[0].width = 240.0;
+    page.blocks[2].x = 320.0;
+    page.blocks[2].width = 150.0;
+    let mut right = block_from_lines(&["The right column."]);
+    right.x = 310.0;
+    right.y = 300.0;
+    right.width = 240.0;
+    right.height = 20.0;
+    page.blocks.push(right);
+    assert_eq!(
+        page.figure_regions(),
+        vec![Coordinate::from_rect(300.0, 320.0, 550.0, 400.0)]
+    );
+
+    assert!(Page::new(600.0, 800.0, 1).figure_regions().is_empty());
+}
+
+#[test]
+fn test_export_figures() {
+    let mut config = ParserConfig::new();
+    let image_path = "/tmp/rsrpp-export-page-1.jpg";
+    let image = Mat::new_rows_cols_with_default(
+        800,
+        600,
+        opencv::core::CV_8UC3,
+        opencv::core::Scalar::all(255.0),
+    )
+    .unwrap();
+    imgcodecs::imwrite_def(image_path, &image).unwrap();
+    config.pdf_figures.insert(1, image_path.to_string());
+
+    let out_dir = PathBuf::from("/tmp/rsrpp-exported-figures");
+    let paths = config.export_figures(&vec![figure_page()], &out_dir).unwrap();
+    assert_eq!(paths, vec![out_dir.join("figure_1_1.png")]);
+    assert!(paths[0].exists());
+
+    // the page image is required
+    config.pdf_figures.clear();
+    assert!(config.export_figures(&vec![figure_page()], &out_dir).is_err());
+
+    let _ = std::fs::remove_dir_all(&out_dir);
+    let _ = std::fs::remove_file(image_path);
+}
+
+#[tokio::test]
+async fn test_export_figures_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+
+    let regions = pages.iter().map(|page| page.figure_regions().len()).sum::<usize>();
+    assert!(regions > 0);
+    let out_dir = PathBuf::from("/tmp/rsrpp-attention-figures");
+    let paths = config.export_figures(&pages, &out_dir).unwrap();
+    assert_eq!(paths.len(), regions);
+
+    let _ = std::fs::remove_dir_all(&out_dir);
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_parse_associate_captions() {
+    let mut page = figure_page();
+    // Table 1 is set below its caption
+    page.tables.push(detected_table(100.0, 630.0, 500.0, 700.0));
+    let mut pages = vec![page];
+    parse_associate_captions(&ParserConfig::new(), &mut pages).unwrap();
+
+    let captioned_figures = &pages[0].captioned_figures;
+    assert_eq!(captioned_figures.len(), 2);
+    assert_eq!(captioned_figures[0].caption, "Figure 1: The model.");
+    assert_eq!(captioned_figures[0].kind, CaptionKind::Figure);
+    assert_eq!(captioned_figures[0].label, "1");
+    assert_eq!(
+        captioned_figures[0].region,
+        Coordinate::from_rect(50.0, 120.0, 550.0, 400.0)
+    );
+    assert_eq!(captioned_figures[0].page, 1);
+    assert_eq!(captioned_figures[1].kind, CaptionKind::Table);
+    assert_eq!(
+        captioned_figures[1].region,
+        Coordinate::from_rect(100.0, 630.0, 500.0, 700.0)
+    );
+
+    // a caption without a region is not paired
+    let mut pages = vec![figure_page()];
+    parse_associate_captions(&ParserConfig::new(), &mut pages).unwrap();
+    assert_eq!(pages[0].captioned_figures.len(), 1);
+}
+
+fn column_page(page_number: PageNumber, columns: usize) -> Page {
+    let mut page = Page::new(600.0, 800.0, page_number);
+    let column_width = 540.0 / columns as f32;
+    // pdftotext emits the blocks row by row across the columns
+    for row in 0..3 {
+        for column in 0..columns {
+            let text = format!("column {} row {}", column, row);
+            let mut block = block_from_lines(&[&text, "continues here", "and ends here."]);
+            block.x = 30.0 + column_width * column as f32;
+            block.y = 100.0 + 200.0 * row as f32;
+            block.width = column_width - 20.0;
+            for line in block.lines.iter_mut() {
+                line.width = column_width - 20.0;
+            }
+            page.blocks.push(block);
+        }
+    }
+    return page;
+}
+
+#[test]
+fn test_adjst_columns_three_columns() {
+    let config = ParserConfig::new();
+    let mut pages = vec![column_page(1, 3), column_page(2, 3)];
+    adjst_columns(&mut pages, &config);
+
+    for page in pages.iter() {
+        assert_eq!(page.number_of_columns, 3);
+        let order =
+            page.blocks.iter().map(|block| block.lines[0].get_text()).collect::<Vec<String>>();
+        let expected = (0..3)
+            .flat_map(|column| (0..3).map(move |row| format!("column {} row {}", column, row)))
+            .collect::<Vec<String>>();
+        assert_eq!(order, expected);
+    }
+    assert_eq!(estimate_columns(&pages), 3);
+
+    let mut pages = vec![column_page(1, 2)];
+    adjst_columns(&mut pages, &config);
+    assert_eq!(pages[0].number_of_columns, 2);
+    assert_eq!(pages[0].blocks[2].lines[0].get_text(), "column 0 row 2");
+    assert_eq!(pages[0].blocks[3].lines[0].get_text(), "column 1 row 0");
+
+    let mut pages = vec![column_page(1, 1)];
+    adjst_columns(&mut pages, &config);
+    assert_eq!(pages[0].number_of_columns, 1);
+}
+
+#[test]
+fn test_adjst_columns_sorts_columns_by_y() {
+    let config = ParserConfig::new();
+    let mut page = column_page(1, 2);
+    // emit the blocks of each column out of vertical order
+    page.blocks.reverse();
+    page.blocks.swap(0, 3);
+    let mut pages = vec![page];
+    adjst_columns(&mut pages, &config);
+
+    let order =
+        pages[0].blocks.iter().map(|block| block.lines[0].get_text()).collect::<Vec<String>>();
+    assert_eq!(
+        order,
+        vec![
+            "column 0 row 0",
+            "column 0 row 1",
+            "column 0 row 2",
+            "column 1 row 0",
+            "column 1 row 1",
+            "column 1 row 2",
+        ]
+    );
+    for column in pages[0].blocks.chunks(3) {
+        assert!(column.windows(2).all(|pair| pair[0].y < pair[1].y));
+    }
+}
+
+#[test]
+fn test_section_abstract_text() {
+    let sections = vec![
+        Section {
+            title: "ABSTRACT".to_string(),
+            contents: vec![
+                "  We propose a model.".to_string(),
+                "It works.  ".to_string(),
+            ],
+            ..Default::default()
+        },
+        Section {
+            title: "Introduction".to_string(),
+            contents: vec!["PDFs are everywhere.".to_string()],
+            ..Default::default()
+        },
+    ];
+    assert_eq!(
+        Section::abstract_text(&sections),
+        Some("We propose a model.\nIt works.".to_string())
+    );
+    assert_eq!(Section::abstract_text(&sections[1..]), None);
+
+    // without an Abstract heading, the blocks before the first heading form the abstract
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut config = ParserConfig::new();
+    config.sections.push((1, "1 Introduction".to_string()));
+    page.blocks.push(block_from_lines(&["We propose a model."]));
+    page.blocks.push(block_from_lines(&["1 Introduction"]));
+    page.blocks.push(block_from_lines(&["PDFs are everywhere."]));
+    let mut pages = vec![page];
+    parse_extract_secsions(&mut config, &mut pages).unwrap();
+    let paper = Paper::from_pages(pages, &config);
+    assert_eq!(
+        paper.abstract_text(),
+        Some("We propose a model.".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_abstract_text_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages(&pages);
+
+    let abstract_text = Section::abstract_text(&sections).unwrap();
+    assert!(abstract_text.starts_with("The dominant sequence transduction models"));
+
+    let _ = config.clean_files();
+}
+
+fn front_page() -> Page {
+    let mut page = Page::new(612.0, 792.0, 1);
+    let mut title = block_at(150.0, 100.0, 300.0, &["Attention Is All", "You Need"]);
+    title
+        .lines
+        .iter_mut()
+        .flat_map(|line| line.words.iter_mut())
+        .for_each(|word| word.height = 17.0);
+    page.blocks.push(title);
+    page.blocks.push(block_at(
+        100.0,
+        160.0,
+        150.0,
+        &["Ashish Vaswani∗", "Google Brain", "avaswani@google.com"],
+    ));
+    page.blocks.push(block_at(
+        300.0,
+        160.0,
+        150.0,
+        &["Noam Shazeer∗ Niki Parmar∗", "Google Research"],
+    ));
+    page.blocks.push(block_at(
+        100.0,
+        220.0,
+        400.0,
+        &[
+            "Llion Jones, Aidan N. Gomez and Łukasz Kaiser",
+            "University of Toronto",
+        ],
+    ));
+    page.blocks.push(block_at(250.0, 280.0, 100.0, &["Abstract"]));
+    page.blocks.push(block_at(
+        100.0,
+        300.0,
+        400.0,
+        &["The dominant sequence transduction models are based on Ashish Vaswani"],
+    ));
+    return page;
+}
+
+#[test]
+fn test_extract_authors() {
+    let pages = vec![front_page()];
+    assert_eq!(
+        extract_authors(&pages),
+        vec![
+            "Ashish Vaswani",
+            "Noam Shazeer",
+            "Niki Parmar",
+            "Llion Jones",
+            "Aidan N. Gomez",
+            "Łukasz Kaiser",
+        ]
+    );
+    assert_eq!(PaperMetadata::from_pages(&pages).authors.len(), 6);
+    assert!(extract_authors(&[]).is_empty());
+}
+
+#[test]
+fn test_extract_authors_brand_like_names() {
+    // names that are also company names are kept unless the emails name the company
+    let mut page = Page::new(612.0, 792.0, 1);
+    let mut title = block_at(150.0, 100.0, 300.0, &["A Study of Attention"]);
+    title
+        .lines
+        .iter_mut()
+        .flat_map(|line| line.words.iter_mut())
+        .for_each(|word| word.height = 17.0);
+    page.blocks.push(title);
+    page.blocks.push(block_at(
+        100.0,
+        160.0,
+        400.0,
+        &["Ai Chen and Meta Brown", "Acme AI", "ai.chen@acme.com"],
+    ));
+    page.blocks.push(block_at(250.0, 280.0, 100.0, &["Abstract"]));
+
+    assert_eq!(extract_authors(&[page]), vec!["Ai Chen", "Meta Brown"]);
+}
+
+#[test]
+fn test_paper_title() {
+    let pages = vec![front_page()];
+    let mut config = ParserConfig::new();
+    assert_eq!(
+        config.paper_title(&pages),
+        Some("Attention Is All You Need".to_string())
+    );
+
+    config.pdf_info.insert(
+        "title".to_string(),
+        "Attention Is All You Need (v7)".to_string(),
+    );
+    assert_eq!(
+        config.paper_title(&pages),
+        Some("Attention Is All You Need (v7)".to_string())
+    );
+    // file names in the metadata are ignored
+    config.pdf_info.insert("title".to_string(), "main.tex".to_string());
+    assert_eq!(
+        config.paper_title(&pages),
+        Some("Attention Is All You Need".to_string())
+    );
+    assert_eq!(config.paper_title(&vec![]), None);
+}
+
+#[tokio::test]
+async fn test_paper_title_and_authors_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+
+    let title = config.paper_title(&pages).unwrap();
+    assert!(title.contains("Attention Is All You Need"));
+    let authors = extract_authors(&pages);
+    assert!(authors.len() >= 3);
+    assert!(authors.contains(&"Ashish Vaswani".to_string()));
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_extract_arxiv_id() {
+    // the watermark is a narrow vertical block in the left margin of the first page
+    let html = scraper::Html::parse_document(
+        r#"<doc><page width="612" height="792"><flow>
+        <block xMin="20" yMin="220" xMax="36" yMax="560"><line xMin="20" yMin="220" xMax="36" yMax="560">
+        <word>arXiv:1706.03762v7</word><word>[cs.CL]</word><word>2</word><word>Aug</word>
+        </line></block></flow></page></doc>"#,
+    );
+    assert_eq!(extract_arxiv_id(&html), Some("1706.03762".to_string()));
+
+    // an arXiv identifier cited in the body text or the references is not a watermark
+    let html = scraper::Html::parse_document(
+        r#"<doc><page width="612" height="792"><flow>
+        <block xMin="50" yMin="100" xMax="300" yMax="112"><line xMin="50" yMin="100" xMax="300" yMax="112">
+        <word>See</word><word>arXiv:</word><word>2308.1037</word>
+        </line></block></flow></page>
+        <page width="612" height="792"><flow>
+        <block xMin="20" yMin="220" xMax="36" yMax="560"><line xMin="20" yMin="220" xMax="36" yMax="560">
+        <word>arXiv:2308.10370</word>
+        </line></block></flow></page></doc>"#,
+    );
+    assert_eq!(extract_arxiv_id(&html), None);
+
+    let html = scraper::Html::parse_document("<doc><page><word>No</word></page></doc>");
+    assert_eq!(extract_arxiv_id(&html), None);
+
+    let mut config = ParserConfig::new();
+    assert_eq!(config.arxiv_id(), None);
+    config.pdf_info.insert("arxiv_id".to_string(), "1706.03762".to_string());
+    assert_eq!(config.arxiv_id(), Some("1706.03762".to_string()));
+}
+
+#[tokio::test]
+async fn test_arxiv_id_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let _ = parse(url, &mut config, false).await.unwrap();
+    assert_eq!(config.arxiv_id(), Some("1706.03762".to_string()));
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_parse_references_heuristic() {
+    let text = "[1] A. Smith, B. Jones, and C. Doe. Deep learning for PDFs. In Proc. ACL, \
+                pages 1-10, 2020. doi:10.1/x. \
+                [2] Jimmy Lei Ba, Jamie Ryan Kiros, and Geoffrey E Hinton. Layer normalization. \
+                arXiv preprint arXiv:1607.06450, 2016. \
+                [3] Kaiming He. Is attention all you need? https://example.com/paper.pdf, 2021.";
+    let references = parse_references_heuristic(text);
+    assert_eq!(references.len(), 3);
+
+    assert!(references[0].text.starts_with("[1] A. Smith"));
+    assert_eq!(
+        references[0].authors,
+        vec!["A. Smith", "B. Jones", "C. Doe"]
+    );
+    assert_eq!(
+        references[0].title.as_deref(),
+        Some("Deep learning for PDFs")
+    );
+    assert_eq!(references[0].year, Some(2020));
+    assert_eq!(references[0].doi.as_deref(), Some("10.1/x"));
+    assert_eq!(references[0].arxiv_id, None);
+
+    assert_eq!(references[1].authors.len(), 3);
+    assert_eq!(references[1].title.as_deref(), Some("Layer normalization"));
+    assert_eq!(references[1].arxiv_id.as_deref(), Some("1607.06450"));
+    assert_eq!(references[1].year, Some(2016));
+
+    assert_eq!(
+        references[2].title.as_deref(),
+        Some("Is attention all you need?")
+    );
+    assert_eq!(
+        references[2].url.as_deref(),
+        Some("https://example.com/paper.pdf")
+    );
+    assert_eq!(references[2].year, Some(2021));
+}
+
+#[test]
+fn test_parse_references_heuristic_author_year() {
+    let text = "Smith, J., Jones, B. 2020. A study of parsing. Journal of Parsing, 3(2). \
+                Doe, K. 2019a. Another study. arXiv:2010.12345v2.";
+    assert_eq!(split_reference_text(text).len(), 2);
+
+    let references = parse_references_heuristic(text);
+    assert_eq!(references[0].authors, vec!["Smith, J.", "Jones, B."]);
+    assert_eq!(references[0].year, Some(2020));
+    assert_eq!(references[0].title.as_deref(), Some("A study of parsing"));
+    assert_eq!(references[1].authors, vec!["Doe, K."]);
+    assert_eq!(references[1].year, Some(2019));
+    assert_eq!(references[1].arxiv_id.as_deref(), Some("2010.12345"));
+    assert!(parse_references_heuristic("").is_empty());
+}
+
+#[test]
+fn test_references_raw() {
+    // a numbered reference broken across two columns
+    let numbered = vec![
+        Section {
+            title: "Introduction".to_string(),
+            contents: vec!["[1] is not a reference here.".to_string()],
+            ..Default::default()
+        },
+        Section {
+            title: "References".to_string(),
+            contents: vec![
+                "[1] A. Smith. A paper. 2020. [2] B. Jones. Another".to_string(),
+                "paper. 2021.".to_string(),
+                "[3] C. Doe. A third paper. 2022.".to_string(),
+            ],
+            ..Default::default()
+        },
+    ];
+    assert_eq!(
+        Section::references_raw(&numbered),
+        vec![
+            "[1] A. Smith. A paper. 2020.",
+            "[2] B. Jones. Another paper. 2021.",
+            "[3] C. Doe. A third paper. 2022.",
+        ]
+    );
+
+    // hanging indents with one paragraph per reference
+    let hanging = vec![Section {
+        title: "Bibliography".to_string(),
+        contents: vec![
+            "Smith, J. 2020. A study of parsing. Journal of Parsing.".to_string(),
+            "Jones, B. 2021. A study broken across".to_string(),
+            "columns. Journal of Layout.".to_string(),
+            "Doe, K. 2019. Two in one. Venue. Lee, M. 2018. Merged entry. Venue.".to_string(),
+        ],
+        ..Default::default()
+    }];
+    assert_eq!(
+        Section::references_raw(&hanging),
+        vec![
+            "Smith, J. 2020. A study of parsing. Journal of Parsing.",
+            "Jones, B. 2021. A study broken across columns. Journal of Layout.",
+            "Doe, K. 2019. Two in one. Venue.",
+            "Lee, M. 2018. Merged entry. Venue.",
+        ]
+    );
+
+    assert!(Section::references_raw(&numbered[..1]).is_empty());
+}
+
+#[tokio::test]
+async fn test_references_raw_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages(&pages);
+
+    // the paper lists 40 references
+    let references = Section::references_raw(&sections);
+    assert!(38 <= references.len() && references.len() <= 42);
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_find_citations_numeric() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    for (text, section) in [
+        (
+            "Recurrent models [1] and attention [2, 3] are common.",
+            "Introduction",
+        ),
+        ("Convolutions [2-4] were tried [9].", "Background"),
+        ("The values in [0, 1] are scaled.", "Method"),
+        ("[1] A. Smith. A paper. 2020.", "References"),
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = section.to_string();
+        page.blocks.push(block);
+    }
+    let references = parse_references_heuristic(
+        "[1] A. Smith. A paper. 2020. [2] B. Jones. Another paper. 2021. \
+         [3] C. Doe. A third paper. 2022. [4] D. Lee. A fourth paper. 2023.",
+    );
+
+    let citations = find_citations(&[page], &references);
+    assert_eq!(
+        citations[0],
+        Citation {
+            marker: "[1]".to_string(),
+            section: "Introduction".to_string(),
+            reference_index: Some(0),
+        }
+    );
+    let indices = citations.iter().map(|citation| citation.reference_index).collect::<Vec<_>>();
+    // "[9]" has no reference, and the interval in the Method section is not a citation
+    assert_eq!(
+        indices,
+        vec![Some(0), Some(1), Some(2), Some(1), Some(2), Some(3), None]
+    );
+    assert_eq!(citations[1].marker, "[2, 3]");
+    assert_eq!(citations[6].section, "Background");
+}
+
+#[test]
+fn test_find_citations_author_year() {
+    let mut page = Page::new(600.0, 800.0, 1);
+    let mut block = block_from_lines(&[
+        "Transformers (Vaswani et al., 2017; Devlin and Chang, 2019) build on Bahdanau et al. (2015).",
+    ]);
+    block.section = "Introduction".to_string();
+    page.blocks.push(block);
+    let references = parse_references_heuristic(
+        "Bahdanau, D., Cho, K. 2015. Neural machine translation. ICLR. \
+         Devlin, J., Chang, M. 2019. BERT. NAACL. \
+         Vaswani, A., Shazeer, N. 2017. Attention is all you need. NeurIPS.",
+    );
+
+    let citations = find_citations(&[page], &references);
+    assert_eq!(citations.len(), 3);
+    assert_eq!(citations[0].marker, "Vaswani et al., 2017");
+    assert_eq!(citations[0].reference_index, Some(2));
+    assert_eq!(citations[1].marker, "Devlin and Chang, 2019");
+    assert_eq!(citations[1].reference_index, Some(1));
+    assert_eq!(citations[2].marker, "Bahdanau et al. (2015)");
+    assert_eq!(citations[2].reference_index, Some(0));
+
+    // bracketed numbers in an author-year paper are not numeric citations
+    let mut page = Page::new(600.0, 800.0, 1);
+    for text in [
+        "Transformers (Vaswani et al., 2017; Devlin and Chang, 2019) build on Bahdanau et al. (2015).",
+        "The weights lie in [0, 1] and are clipped as in [3] (Devlin and Chang, 2019).",
+    ] {
+        let mut block = block_from_lines(&[text]);
+        block.section = "Introduction".to_string();
+        page.blocks.push(block);
+    }
+    let citations = find_citations(&[page], &references);
+    assert_eq!(citations.len(), 4);
+    assert!(citations.iter().all(|citation| !citation.marker.starts_with('[')));
+
+    assert!(trigram_similarity("Vaswani", "vaswani") == 1.0);
+    assert!(trigram_similarity("Vaswani", "Devlin") < 0.5);
+}
+
+#[tokio::test]
+async fn test_find_citations_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages(&pages);
+    let references = parse_references_heuristic(&Section::references_raw(&sections).join(" "));
+
+    let citations = find_citations(&pages, &references);
+    let first = citations.iter().find(|citation| citation.marker == "[1]").unwrap();
+    assert_eq!(first.reference_index, Some(0));
+    assert!(references[0].text.starts_with("[1]"));
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_outline_levels() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<text top="100" left="50" width="60" height="12" font="1"><b>Abstract</b></text>
+<text top="200" left="50" width="80" height="12" font="1"><b>1 Introduction</b></text>
+</page>
+<page number="2" position="absolute" top="0" left="0" height="792" width="612">
+<text top="100" left="50" width="20" height="12" font="1"><b>3.1</b></text>
+<text top="100" left="80" width="120" height="12" font="1"><b>Encoder and Decoder Stacks</b></text>
+<text top="300" left="50" width="120" height="12" font="1"><b>3.2.1 Scaled Dot-Product Attention</b></text>
+</page>
+</pdf2xml>"#;
+    let sections = vec![
+        (1, "Abstract".to_string()),
+        (1, "Introduction".to_string()),
+        (2, "Encoder and Decoder Stacks".to_string()),
+        (2, "Scaled Dot-Product Attention".to_string()),
+    ];
+
+    let outline = outline_levels(xml, &sections);
+    assert_eq!(
+        outline,
+        vec![
+            (1, "Abstract".to_string(), 1),
+            (1, "Introduction".to_string(), 1),
+            (2, "Encoder and Decoder Stacks".to_string(), 2),
+            (2, "Scaled Dot-Product Attention".to_string(), 3),
+        ]
+    );
+}
+
+#[test]
+fn test_outline_levels_detected_subsections() {
+    // the subsections are set in a smaller font than the sections
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="12" family="Times" color="#000000"/>
+<fontspec id="2" size="11" family="Times" color="#000000"/>
+<text top="100" left="50" width="80" height="13" font="1"><b>1 Introduction</b></text>
+<text top="120" left="50" width="250" height="11" font="0">Recurrent models have been the standard approach for years.</text>
+<text top="140" left="50" width="80" height="13" font="1"><b>3 Model Architecture</b></text>
+<text top="160" left="50" width="150" height="12" font="2"><b>3.1 Encoder and Decoder Stacks</b></text>
+<text top="180" left="50" width="250" height="11" font="0">The encoder is composed of a stack of identical layers.</text>
+<text top="200" left="50" width="150" height="12" font="2"><b>3.2 Attention</b></text>
+<text top="220" left="50" width="250" height="11" font="0">An attention function maps a query and a set of pairs.</text>
+</page>
+</pdf2xml>"##;
+
+    let sections = detect_section_titles(xml, &ParserConfig::new().section_keywords).unwrap();
+    let outline = outline_levels(xml, &sections);
+    assert_eq!(
+        outline,
+        vec![
+            (1, "Introduction".to_string(), 1),
+            (1, "Model Architecture".to_string(), 1),
+            (1, "Encoder and Decoder Stacks".to_string(), 2),
+            (1, "Attention".to_string(), 2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_extract_outline_attention() {
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let mut outline_config = ParserConfig::new();
+    let outline = extract_outline(url, &mut outline_config).await.unwrap();
+    assert!(outline_config.pdf_figures.is_empty());
+
+    let mut config = ParserConfig::new();
+    let _ = parse(url, &mut config, false).await.unwrap();
+    // the full parse drops headings that no block was assigned to
+    let phantoms = config
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| match diagnostic {
+            ParseDiagnostic::PhantomSection { title, .. } => Some(title.clone()),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+    let titles = outline
+        .iter()
+        .map(|(_, title, _)| title.clone())
+        .filter(|title| !phantoms.contains(title))
+        .collect::<Vec<String>>();
+    let expected = config.sections.iter().map(|(_, title)| title.clone()).collect::<Vec<String>>();
+    assert_eq!(titles, expected);
+    assert!(outline
+        .iter()
+        .any(|(_, title, level)| title == "Encoder and Decoder Stacks" && *level == 2));
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_detect_section_titles_bold_same_size() {
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="10" family="Times-Bold" color="#000000"/>
+<fontspec id="2" size="10" family="Times" color="#231f20"/>
+<text top="100" left="50" width="50" height="11" font="0"><b>Abstract</b></text>
+<text top="120" left="50" width="250" height="11" font="0">We study transformers with a simple and fast method.</text>
+<text top="140" left="50" width="80" height="11" font="1">1 Introduction</text>
+<text top="160" left="50" width="250" height="11" font="0">Recurrent models have been the standard approach.</text>
+<text top="180" left="50" width="60" height="11" font="0"><b>Residual Dropout</b></text>
+<text top="180" left="114" width="186" height="11" font="0">We apply dropout to the output of each layer.</text>
+<text top="200" left="50" width="40" height="11" font="0"><b>Figure 1:</b></text>
+<text top="200" left="94" width="206" height="11" font="0">The model architecture of the transformer.</text>
+</page>
+<page number="2" position="absolute" top="0" left="0" height="792" width="612">
+<text top="100" left="50" width="10" height="11" font="2"><b>2</b></text>
+<text top="100" left="64" width="50" height="11" font="2"><b>Method</b></text>
+<text top="100" left="330" width="250" height="11" font="0">Text in the right column on the same line.</text>
+<text top="120" left="50" width="250" height="11" font="0">Our method replaces recurrence with attention layers.</text>
+<text top="140" left="50" width="60" height="11" font="0"><b>References</b></text>
+<text top="160" left="50" width="250" height="11" font="0">[1] A. Smith. A paper on attention. 2020.</text>
+</page>
+</pdf2xml>"##;
+
+    let sections = detect_section_titles(xml, &ParserConfig::new().section_keywords).unwrap();
+    assert_eq!(
+        sections,
+        vec![
+            (1, "Abstract".to_string()),
+            (1, "Introduction".to_string()),
+            (2, "Method".to_string()),
+            (2, "References".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_detect_section_titles_larger_headings() {
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="12" family="Times" color="#000000"/>
+<fontspec id="2" size="12" family="Times" color="#0000ff"/>
+<text top="100" left="50" width="80" height="13" font="1"><b>1 Introduction</b></text>
+<text top="120" left="50" width="250" height="11" font="0">Recurrent models have been the standard approach.</text>
+<text top="140" left="50" width="250" height="11" font="0"><b>Bold emphasis</b> in a paragraph of body text.</text>
+<text top="160" left="50" width="80" height="13" font="2"><b>2 Background</b></text>
+<text top="180" left="50" width="250" height="11" font="0">Attention has been used in many models before.</text>
+</page>
+</pdf2xml>"##;
+
+    let sections = detect_section_titles(xml, &ParserConfig::new().section_keywords).unwrap();
+    assert_eq!(
+        sections,
+        vec![
+            (1, "Introduction".to_string()),
+            (1, "Background".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_detect_section_titles_subsections() {
+    // the subsections are bold at the body text size, with the number inline or on its own
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="12" family="Times" color="#000000"/>
+<text top="100" left="50" width="80" height="13" font="1"><b>1 Introduction</b></text>
+<text top="120" left="50" width="250" height="11" font="0">Recurrent models have been the standard approach.</text>
+<text top="140" left="50" width="80" height="13" font="1"><b>3 Model Architecture</b></text>
+<text top="160" left="50" width="20" height="11" font="0"><b>3.1</b></text>
+<text top="160" left="75" width="150" height="11" font="0"><b>Encoder and Decoder Stacks</b></text>
+<text top="180" left="50" width="250" height="11" font="0">The encoder is composed of a stack of identical layers.</text>
+<text top="200" left="50" width="150" height="11" font="0"><b>3.2 Attention</b></text>
+<text top="220" left="50" width="250" height="11" font="0">An attention function maps a query to an output.</text>
+<text top="240" left="50" width="40" height="11" font="0"><b>Table 1:</b></text>
+<text top="260" left="50" width="20" height="11" font="0"><b>0.5</b></text>
+<text top="280" left="50" width="80" height="13" font="1"><b>References</b></text>
+</page>
+</pdf2xml>"##;
+
+    let sections = detect_section_titles(xml, &ParserConfig::new().section_keywords).unwrap();
+    assert_eq!(
+        sections,
+        vec![
+            (1, "Introduction".to_string()),
+            (1, "Model Architecture".to_string()),
+            (1, "Encoder and Decoder Stacks".to_string()),
+            (1, "Attention".to_string()),
+            (1, "References".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_detect_section_titles_keyword_in_author_block() {
+    // the "Abstract" label of the author block comes before the headings in a smaller font
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="12" family="Times" color="#000000"/>
+<fontspec id="2" size="8" family="Times" color="#000000"/>
+<text top="80" left="50" width="200" height="9" font="2">Alice Smith, University of Tokyo</text>
+<text top="90" left="50" width="40" height="9" font="2">Abstract</text>
+<text top="100" left="50" width="250" height="9" font="2">We study transformers with a simple and fast method.</text>
+<text top="140" left="50" width="80" height="13" font="1"><b>1 Introduction</b></text>
+<text top="160" left="50" width="250" height="11" font="0">Recurrent models have been the standard approach for years.</text>
+<text top="180" left="50" width="80" height="13" font="1"><b>2 Related Work</b></text>
+<text top="200" left="50" width="250" height="11" font="0">Attention has been used in many models before this work.</text>
+<text top="220" left="50" width="80" height="13" font="1"><b>References</b></text>
+</page>
+</pdf2xml>"##;
+
+    let sections = detect_section_titles(xml, &ParserConfig::new().section_keywords).unwrap();
+    assert_eq!(
+        sections,
+        vec![
+            (1, "Introduction".to_string()),
+            (1, "Related Work".to_string()),
+            (1, "References".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_detect_section_titles_custom_keywords() {
+    let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
+<pdf2xml>
+<page number="1" position="absolute" top="0" left="0" height="792" width="612">
+<fontspec id="0" size="10" family="Times" color="#000000"/>
+<fontspec id="1" size="12" family="Times" color="#000000"/>
+<text top="100" left="50" width="80" height="13" font="1"><b>1 Background</b></text>
+<text top="120" left="50" width="250" height="11" font="0">Surveys of the field have been published every few years.</text>
+<text top="140" left="50" width="80" height="13" font="1"><b>2 Methodology</b></text>
+<text top="160" left="50" width="250" height="11" font="0">We collected papers from the main venues of the field.</text>
+<text top="180" left="50" width="80" height="13" font="1"><b>3 Findings</b></text>
+<text top="200" left="50" width="250" height="11" font="0">Most papers evaluate on a small number of benchmarks.</text>
+</page>
+</pdf2xml>"##;
+
+    let mut config = ParserConfig::new();
+    assert!(detect_section_titles(xml, &config.section_keywords).unwrap().is_empty());
+
+    config.section_keywords.push("Methodology".to_string());
+    let sections = detect_section_titles(xml, &config.section_keywords).unwrap();
+    assert_eq!(
+        sections,
+        vec![
+            (1, "Background".to_string()),
+            (1, "Methodology".to_string()),
+            (1, "Findings".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_section_spans_attention() {
+    let mut config = ParserConfig::new();
+    config.track_provenance = true;
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages_with_config(&pages, &config);
+
+    let introduction = sections.iter().find(|section| section.title == "Introduction").unwrap();
+    assert!(!introduction.spans.is_empty());
+    assert_eq!(introduction.spans[0].page, 2);
+    for span in introduction.spans.iter() {
+        let (start, end) = span.text_range;
+        assert!(introduction.contents.contains(&introduction.get_text()[start..end].to_string()));
+    }
+
+    let _ = config.clean_files();
+}
+
+#[test]
+fn test_strip_running_headers() {
+    let mut pages = Vec::new();
+    let bodies = ["on attention", "on recurrence", "on training", "on results"];
+    for page_number in 1..=4 {
+        let mut page = Page::new(600.0, 800.0, page_number);
+        let mut blocks = vec![
+            (20.0, "Journal of Machine Learning Research".to_string()),
+            (
+                100.0,
+                format!("Body text {}.", bodies[page_number as usize - 1]),
+            ),
+            (760.0, "Preprint. Under review.".to_string()),
+            (780.0, format!("{}", page_number * 5)),
+        ];
+        // a header on only two pages is kept
+        if page_number <= 2 {
+            blocks.push((40.0, "Draft".to_string()));
+        }
+        for (y, text) in blocks {
+            let mut block = block_from_lines(&[text.as_str()]);
+            block.y = y + page_number as f32 * 0.5;
+            block.section = "Introduction".to_string();
+            page.blocks.push(block);
+        }
+        pages.push(page);
+    }
+
+    strip_running_headers(&mut pages);
+    for page in pages.iter() {
+        let texts = page.blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+        assert!(texts.iter().any(|text| text.starts_with("Body text")));
+        assert_eq!(texts.len(), if page.page_nubmer <= 2 { 2 } else { 1 });
+    }
+
+    let sections = Section::from_pages(&pages);
+    assert!(sections[0].contents.iter().all(|text| !text.contains("Preprint")));
+}
+
+#[test]
+fn test_strip_running_headers_keeps_body_blocks() {
+    let mut pages = Vec::new();
+    for page_number in 1..=3 {
+        let mut page = Page::new(600.0, 800.0, page_number);
+        for (y, text) in [(20.0, "Preprint"), (300.0, "(1)"), (400.0, "0.5 0.3")] {
+            let mut block = block_from_lines(&[text]);
+            block.y = y;
+            page.blocks.push(block);
+        }
+        pages.push(page);
+    }
+
+    // equation numbers and short table rows repeated mid-page are not running headers
+    strip_running_headers(&mut pages);
+    for page in pages.iter() {
+        let texts = page.blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>();
+        assert_eq!(texts, vec!["(1)".to_string(), "0.5 0.3".to_string()]);
+    }
+}
+
+#[test]
+fn test_remove_margin_line_numbers() {
+    let mut pages = Vec::new();
+    for page_number in 1..=2 {
+        let mut page = Page::new(600.0, 800.0, page_number);
+        let mut body = block_from_lines(&["Our model uses attention.", "It is fast."]);
+        body.x = 70.0;
+        body.y = 100.0;
+        body.width = 460.0;
+        page.blocks.push(body);
+
+        // a numbered list inside the text area is kept
+        let mut list = block_from_lines(&["1", "2", "3", "4", "5"]);
+        list.x = 70.0;
+        list.y = 200.0;
+        list.width = 10.0;
+        page.blocks.push(list);
+
+        // one block per line number in the left margin, continuing across pages
+        for i in 0..10 {
+            let number = (page_number - 1) * 10 + i + 1;
+            let mut block = block_from_lines(&[number.to_string().as_str()]);
+            block.x = 20.0;
+            block.y = 100.0 + 20.0 * i as f32;
+            block.width = 12.0;
+            for line in block.lines.iter_mut() {
+                line.y = block.y;
+            }
+            page.blocks.push(block);
+        }
+        pages.push(page);
+    }
+
+    // decreasing numbers in the right margin are not line numbers
+    for i in 0..5 {
+        let mut block = block_from_lines(&[(5 - i).to_string().as_str()]);
+        block.x = 560.0;
+        block.y = 100.0 + 20.0 * i as f32;
+        block.width = 10.0;
+        for line in block.lines.iter_mut() {
+            line.y = block.y;
+        }
+        pages[1].blocks.push(block);
+    }
+
+    remove_margin_line_numbers(&mut pages);
+    assert_eq!(pages[0].blocks.len(), 2);
+    assert_eq!(pages[0].blocks[1].get_text(), "1 2 3 4 5");
+    assert_eq!(pages[1].blocks.len(), 7);
+    assert!(pages.iter().flat_map(|page| page.blocks.iter()).all(|block| block.x != 20.0));
+}
+
+#[tokio::test]
+async fn test_footnotes_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let pages = parse(url, &mut config, false).await.unwrap();
+    let sections = Section::from_pages(&pages);
+
+    // the contribution notes at the bottom of the first page
+    assert!(sections
+        .iter()
+        .flat_map(|section| section.footnotes.iter())
+        .any(|footnote| footnote.contains("Equal contribution")));
+    assert!(sections
+        .iter()
+        .flat_map(|section| section.contents.iter())
+        .all(|text| !text.contains("Equal contribution")));
+
+    let _ = config.clean_files();
+}
+
+#[tokio::test]
+async fn test_parse_pages_stream_attention() {
+    let mut config = ParserConfig::new();
+    let url = "https://arxiv.org/pdf/1706.03762";
+    let stream = parse_pages_stream(url, &mut config).await.unwrap();
+    let streamed = stream.collect::<Vec<Result<Page, ParseError>>>().await;
+    let streamed = streamed.into_iter().collect::<Result<Vec<Page>, ParseError>>().unwrap();
+    config.clean_files().unwrap();
+
+    let mut config = ParserConfig::new();
+    let pages = parse(url, &mut config, false).await.unwrap();
+    config.clean_files().unwrap();
+    assert_eq!(streamed.len(), pages.len());
+
+    // every line kept by the eager pipeline is streamed on the same page in the same section
+    let lines = |pages: &Vec<Page>| {
+        pages
+            .iter()
+            .flat_map(|page| {
+                page.blocks.iter().flat_map(move |block| {
+                    block
+                        .lines
+                        .iter()
+                        .map(move |line| (page.page_nubmer, line.get_text(), block.section.clone()))
+                })
+            })
+            .collect::<HashSet<(PageNumber, String, String)>>()
+    };
+    let streamed_lines = lines(&streamed);
+    assert!(lines(&pages).is_subset(&streamed_lines));
+    let titles = |pages: &Vec<Page>| {
+        Section::from_pages(pages).into_iter().map(|section| section.title).collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&streamed), titles(&pages));
+
+    // the blocks kept by the eager pipeline are classified alike, and their captions are paired alike
+    for (streamed_page, page) in streamed.iter().zip(pages.iter()) {
+        assert_eq!(streamed_page.page_nubmer, page.page_nubmer);
+        for block in page.blocks.iter() {
+            let streamed_block = streamed_page
+                .blocks
+                .iter()
+                .find(|streamed_block| streamed_block.get_text() == block.get_text());
+            if let Some(streamed_block) = streamed_block {
+                assert_eq!(
+                    streamed_block.block_type,
+                    block.block_type,
+                    "{}",
+                    block.get_text()
+                );
+            }
+        }
+        let labels = |page: &Page| {
+            page.captioned_figures
+                .iter()
+                .map(|figure| format!("{:?} {}", figure.kind, figure.label))
+                .collect::<HashSet<String>>()
+        };
+        assert!(labels(page).is_subset(&labels(streamed_page)));
+        assert_eq!(streamed_page.tables.len(), page.tables.len());
+    }
+}